@@ -0,0 +1,468 @@
+/*
+ * Plugin to turn input from any device into player actions
+ * Gameplay only reads the action events; each input source (mouse, keyboard, gamepad, touch, the autopilot, ...)
+ * writes them.
+ * A new source is a system in the ActionSources set (or, for fixed step sources like the autopilot, a system in
+ * GameplaySet::Input) writing the action events. Buttons are mapped to actions by the rebindable ActionBindings
+ */
+use bevy::{ecs::system::SystemParam, input::InputSystem, prelude::*};
+
+/*
+ * Plugin defintion
+ */
+pub fn actions_plugin(app: &mut App) {
+    app
+        // Read the devices once per frame, so no press or release is missed or seen twice;
+        // the actions are handled in the fixed gameplay schedules
+        .configure_sets(PreUpdate, ActionSources.after(InputSystem))
+        .add_systems(
+            PreUpdate,
+            (
+                read_mouse_actions,
+                read_keyboard_actions,
+                read_gamepad_actions,
+                read_touch_actions,
+            )
+                .in_set(ActionSources),
+        )
+        // Events
+        .add_event::<MovePaddleToEvent>()
+        .add_event::<MovePaddleByEvent>()
+        .add_event::<LaunchPressEvent>()
+        .add_event::<LaunchReleaseEvent>()
+        .add_event::<BackEvent>()
+        .add_event::<PauseEvent>()
+        // Resources
+        .init_resource::<ActionBindings>();
+}
+
+// speed at which held move buttons move the paddle, in pixels per second
+const BUTTON_PADDLE_SPEED: f32 = 900.0;
+// gamepad paddle control: top speed at full deflection, in pixels per second, and how fast it is reached
+// (or given up), in pixels per second squared
+const GAMEPAD_PADDLE_MAX_SPEED: f32 = 1200.0;
+const GAMEPAD_PADDLE_ACCELERATION: f32 = 5000.0;
+const GAMEPAD_PADDLE_DECELERATION: f32 = 12000.0;
+// stick deflections below this are ignored, so a resting stick does not drift
+const GAMEPAD_STICK_DEAD_ZONE: f32 = 0.15;
+
+/*
+ * ================================================================================================================
+ * START - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * The cursor points where the paddle should go (and aims held balls); buttons are mapped by the bindings
+ */
+fn read_mouse_actions(
+    // Singles
+    camera: Single<(&Camera, &GlobalTransform)>,
+    // Globals
+    time: Res<Time>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    bindings: Res<ActionBindings>,
+    mut pointer_world_position: Local<Option<Vec2>>,
+    // Events
+    mut cursor_evr: EventReader<CursorMoved>,
+    mut action_writers: ActionWriters,
+) {
+    let (camera, camera_transform) = camera.into_inner();
+
+    for event in cursor_evr.read() {
+        trace!("Mouse moved");
+
+        if let Ok(world_position) = camera.viewport_to_world_2d(camera_transform, event.position) {
+            *pointer_world_position = Some(world_position);
+            action_writers.move_paddle_to(world_position);
+        }
+    }
+
+    for (mouse_button, action) in bindings.mouse_buttons.iter() {
+        action_writers.write_button_action(
+            *action,
+            mouse_input.just_pressed(*mouse_button),
+            mouse_input.pressed(*mouse_button),
+            mouse_input.just_released(*mouse_button),
+            *pointer_world_position,
+            time.delta_secs(),
+        );
+    }
+}
+
+fn read_keyboard_actions(
+    // Globals
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<ActionBindings>,
+    // Events
+    mut action_writers: ActionWriters,
+) {
+    for (key, action) in bindings.keys.iter() {
+        action_writers.write_button_action(
+            *action,
+            keyboard_input.just_pressed(*key),
+            keyboard_input.pressed(*key),
+            keyboard_input.just_released(*key),
+            None,
+            time.delta_secs(),
+        );
+    }
+}
+
+/*
+ * The left stick or the triggers (right minus left) steer the paddle; the paddle speeds up and slows down gradually,
+ * so small deflections allow precise moves. The paddle is moved relative to where it is, so a mouse moved in between
+ * simply takes over from there. Buttons are mapped by the bindings
+ */
+fn read_gamepad_actions(
+    // Globals
+    time: Res<Time>,
+    bindings: Res<ActionBindings>,
+    mut paddle_speed: Local<f32>,
+    // Events
+    mut action_writers: ActionWriters,
+    // Queries
+    gamepads: Query<(Entity, &Gamepad)>,
+) {
+    let Some(gamepad) = player_one_gamepad(&gamepads).and_then(|gamepad_entity| {
+        gamepads
+            .get(gamepad_entity)
+            .ok()
+            .map(|(_gamepad_entity, gamepad)| gamepad)
+    }) else {
+        *paddle_speed = 0.0;
+        return;
+    };
+
+    let mut deflection = gamepad.left_stick().x;
+    if f32::abs(deflection) < GAMEPAD_STICK_DEAD_ZONE {
+        deflection = 0.0;
+    }
+    deflection += gamepad.get(GamepadButton::RightTrigger2).unwrap_or(0.0)
+        - gamepad.get(GamepadButton::LeftTrigger2).unwrap_or(0.0);
+
+    let target_speed = deflection.clamp(-1.0, 1.0) * GAMEPAD_PADDLE_MAX_SPEED;
+    // speeding up in the same direction accelerates, anything else brakes
+    let acceleration = if target_speed * *paddle_speed >= 0.0
+        && f32::abs(target_speed) > f32::abs(*paddle_speed)
+    {
+        GAMEPAD_PADDLE_ACCELERATION
+    } else {
+        GAMEPAD_PADDLE_DECELERATION
+    };
+    let max_speed_change = acceleration * time.delta_secs();
+    *paddle_speed += (target_speed - *paddle_speed).clamp(-max_speed_change, max_speed_change);
+
+    if *paddle_speed != 0.0 {
+        action_writers.move_paddle_by.write(MovePaddleByEvent {
+            distance: *paddle_speed * time.delta_secs(),
+        });
+    }
+
+    for (gamepad_button, action) in bindings.gamepad_buttons.iter() {
+        action_writers.write_button_action(
+            *action,
+            gamepad.just_pressed(*gamepad_button),
+            gamepad.pressed(*gamepad_button),
+            gamepad.just_released(*gamepad_button),
+            None,
+            time.delta_secs(),
+        );
+    }
+}
+
+/*
+ * The first finger works like the mouse with the launch button bound: touching presses, lifting releases
+ */
+fn read_touch_actions(
+    // Singles
+    camera: Single<(&Camera, &GlobalTransform)>,
+    // Globals
+    touches: Res<Touches>,
+    // Events
+    mut action_writers: ActionWriters,
+) {
+    let (camera, camera_transform) = camera.into_inner();
+
+    let to_world = |touch_position: Vec2| {
+        camera
+            .viewport_to_world_2d(camera_transform, touch_position)
+            .ok()
+    };
+
+    if let Some(touch) = touches.first_pressed_position().and_then(to_world) {
+        action_writers.move_paddle_to(touch);
+    }
+
+    // only the first finger is used
+    if let Some(touch_world_position) = touches
+        .iter_just_pressed()
+        .next()
+        .and_then(|touch| to_world(touch.position()))
+    {
+        action_writers.launch_press.write(LaunchPressEvent {
+            pointer: Some(touch_world_position),
+        });
+    }
+
+    if touches.iter_just_released().next().is_some() {
+        action_writers.launch_release.write(LaunchReleaseEvent);
+    }
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin functions
+ * ================================================================================================================
+ */
+
+/*
+ * Player one plays with the gamepad connected first; any others are left to player two in co-op
+ */
+pub fn player_one_gamepad(gamepads: &Query<(Entity, &Gamepad)>) -> Option<Entity> {
+    gamepads
+        .iter()
+        .map(|(gamepad_entity, _gamepad)| gamepad_entity)
+        .min()
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin functions
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin System Params
+ * ================================================================================================================
+ */
+
+/*
+ * All action events, for sources writing more than one kind
+ */
+#[derive(SystemParam)]
+pub struct ActionWriters<'w> {
+    pub move_paddle_to: EventWriter<'w, MovePaddleToEvent>,
+    pub move_paddle_by: EventWriter<'w, MovePaddleByEvent>,
+    pub launch_press: EventWriter<'w, LaunchPressEvent>,
+    pub launch_release: EventWriter<'w, LaunchReleaseEvent>,
+    pub back: EventWriter<'w, BackEvent>,
+    pub pause: EventWriter<'w, PauseEvent>,
+}
+
+impl ActionWriters<'_> {
+    pub fn move_paddle_to(&mut self, position: Vec2) {
+        self.move_paddle_to.write(MovePaddleToEvent { position });
+    }
+
+    /*
+     * Writes the action of a bound button; launching is held, moving repeats while held, the others trigger once
+     */
+    pub fn write_button_action(
+        &mut self,
+        // Parameters
+        action: ButtonAction,
+        just_pressed: bool,
+        pressed: bool,
+        just_released: bool,
+        pointer: Option<Vec2>,
+        delta_secs: f32,
+    ) {
+        match action {
+            ButtonAction::Launch => {
+                if just_pressed {
+                    trace!("Launch pressed");
+                    self.launch_press.write(LaunchPressEvent { pointer });
+                }
+                if just_released {
+                    trace!("Launch released");
+                    self.launch_release.write(LaunchReleaseEvent);
+                }
+            }
+            ButtonAction::Back => {
+                if just_pressed {
+                    trace!("Back pressed");
+                    self.back.write(BackEvent);
+                }
+            }
+            ButtonAction::Pause => {
+                if just_pressed {
+                    trace!("Pause pressed");
+                    self.pause.write(PauseEvent);
+                }
+            }
+            ButtonAction::MovePaddleLeft => {
+                if pressed {
+                    self.move_paddle_by.write(MovePaddleByEvent {
+                        distance: -BUTTON_PADDLE_SPEED * delta_secs,
+                    });
+                }
+            }
+            ButtonAction::MovePaddleRight => {
+                if pressed {
+                    self.move_paddle_by.write(MovePaddleByEvent {
+                        distance: BUTTON_PADDLE_SPEED * delta_secs,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin System Params
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin System Sets
+ * ================================================================================================================
+ */
+
+/*
+ * Systems turning device input into action events
+ */
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionSources;
+
+/*
+ * ================================================================================================================
+ * END - Plugin System Sets
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Enumerations
+ * ================================================================================================================
+ */
+
+/*
+ * Actions a button can be bound to
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ButtonAction {
+    Launch,
+    Back,
+    Pause,
+    MovePaddleLeft,
+    MovePaddleRight,
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Enumerations
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * Start - Plugin Events
+ * ================================================================================================================
+ */
+
+/*
+ * Moves the paddle towards a position in world coordinates; while a ball is held, aims it instead
+ */
+#[derive(Event)]
+pub struct MovePaddleToEvent {
+    pub position: Vec2,
+}
+
+/*
+ * Moves the paddle sideways by a distance in pixels (negative = left); while a ball is held, aims it instead
+ */
+#[derive(Event)]
+pub struct MovePaddleByEvent {
+    pub distance: f32,
+}
+
+/*
+ * Takes a new ball, or starts aiming the held ones
+ * Sources with a pointer pass its world position; the press then only counts on the paddle, and aims from there
+ */
+#[derive(Event)]
+pub struct LaunchPressEvent {
+    pub pointer: Option<Vec2>,
+}
+
+/*
+ * Launches the held balls
+ */
+#[derive(Event)]
+pub struct LaunchReleaseEvent;
+
+/*
+ * Leaves the current level
+ */
+#[derive(Event)]
+pub struct BackEvent;
+
+/*
+ * Pauses or resumes the game
+ */
+#[derive(Event)]
+pub struct PauseEvent;
+
+/*
+ * ================================================================================================================
+ * END - Plugin Events
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Resources
+ * ================================================================================================================
+ */
+
+/*
+ * Which button triggers which action; a button should only be bound once per device, an action any number of times
+ * Movement keys are unbound by default, since player two uses the arrow keys and A/D in co-op; player one's gamepad
+ * moves with its D-pad, which player two only reads on their own gamepad
+ */
+#[derive(Resource)]
+pub struct ActionBindings {
+    pub mouse_buttons: Vec<(MouseButton, ButtonAction)>,
+    pub keys: Vec<(KeyCode, ButtonAction)>,
+    pub gamepad_buttons: Vec<(GamepadButton, ButtonAction)>,
+}
+
+impl Default for ActionBindings {
+    fn default() -> Self {
+        ActionBindings {
+            mouse_buttons: vec![
+                (MouseButton::Left, ButtonAction::Launch),
+                (MouseButton::Right, ButtonAction::Back),
+            ],
+            keys: vec![
+                (KeyCode::Space, ButtonAction::Launch),
+                (KeyCode::Escape, ButtonAction::Back),
+                (KeyCode::KeyP, ButtonAction::Pause),
+            ],
+            gamepad_buttons: vec![
+                (GamepadButton::South, ButtonAction::Launch),
+                (GamepadButton::East, ButtonAction::Back),
+                (GamepadButton::Start, ButtonAction::Pause),
+                (GamepadButton::DPadLeft, ButtonAction::MovePaddleLeft),
+                (GamepadButton::DPadRight, ButtonAction::MovePaddleRight),
+            ],
+        }
+    }
+}
+
+/*
+ * ================================================================================================================
+ * END - Resources
+ * ================================================================================================================
+ */
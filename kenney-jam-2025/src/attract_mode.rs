@@ -0,0 +1,214 @@
+/*
+ * Plugin to show the game off while nobody plays
+ * After some idle time on the selection screen, the autopilot plays one of the unlocked levels;
+ * any input returns to the selection screen. Demo games never unlock levels for the player
+ */
+use bevy::prelude::*;
+
+use super::{GameRng, GameState, Player, PlayerBall};
+
+use crate::actions::BackEvent;
+use crate::autopilot::{Autopilot, AutopilotSkill};
+use crate::levels::SelectedLevel;
+use crate::selection::LevelSelectedEvent;
+
+/*
+ * Plugin defintion
+ */
+pub fn attract_mode_plugin(app: &mut App) {
+    app
+        // Once per frame, so no cursor movement is missed
+        .add_systems(
+            Update,
+            (
+                start_attract_mode_when_idle.run_if(in_state(GameState::Selection)),
+                end_attract_mode_on_input
+                    .run_if(in_state(GameState::Levels).and(attract_mode_active)),
+            ),
+        )
+        .add_systems(OnExit(GameState::Levels), stop_attract_mode)
+        // Resources
+        .init_resource::<AttractMode>();
+}
+
+// time without any input on the selection screen before a demo starts, in seconds
+const ATTRACT_MODE_IDLE_TIME: f32 = 30.0;
+
+/*
+ * ================================================================================================================
+ * START - Plugin Systems
+ * ================================================================================================================
+ */
+
+fn attract_mode_active(attract_mode: Res<AttractMode>) -> bool {
+    attract_mode.active
+}
+
+fn start_attract_mode_when_idle(
+    // Singles
+    player: Option<Single<&Player>>,
+    // Globals
+    time: Res<Time>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut attract_mode: ResMut<AttractMode>,
+    mut autopilot: ResMut<Autopilot>,
+    mut game_rng: ResMut<GameRng>,
+    mut game_state: ResMut<NextState<GameState>>,
+    // Events
+    mut cursor_evr: EventReader<CursorMoved>,
+    mut level_selected_evw: EventWriter<LevelSelectedEvent>,
+    // Queries
+    player_balls: Query<(), With<PlayerBall>>,
+    gamepads: Query<&Gamepad>,
+) {
+    let mouse_moved = cursor_evr.read().count() > 0;
+
+    // a ball in play means someone is playing, even without touching the mouse
+    if mouse_moved
+        || mouse_input.get_pressed().next().is_some()
+        || keyboard_input.get_pressed().next().is_some()
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_pressed().next().is_some())
+        || !player_balls.is_empty()
+        || autopilot.enabled
+    {
+        attract_mode.idle_time = 0.0;
+        return;
+    }
+
+    attract_mode.idle_time += time.delta_secs();
+    if attract_mode.idle_time < ATTRACT_MODE_IDLE_TIME {
+        return;
+    }
+
+    let highest_selectable_level = player.map_or(SelectedLevel::Level1, |player| {
+        player.into_inner().highest_selectable_level
+    });
+    let demo_level = if attract_mode.demo_level <= highest_selectable_level {
+        attract_mode.demo_level
+    } else {
+        SelectedLevel::Level1
+    };
+
+    attract_mode.active = true;
+    attract_mode.demo_level = next_demo_level(demo_level);
+
+    autopilot.enabled = true;
+    autopilot.skill = AutopilotSkill::CASUAL;
+
+    // the demo draws from its own generator, so the player's games stay the same with or without demos in between
+    std::mem::swap(&mut *game_rng, &mut attract_mode.rng);
+
+    level_selected_evw.write(LevelSelectedEvent {
+        selected_level: demo_level,
+    });
+    game_state.set(GameState::Levels);
+    debug!("Attract mode started with {:?}", demo_level);
+}
+
+/*
+ * The player is back: any mouse, keyboard or gamepad input leaves the demo, just like the back action
+ * Only new presses count, so a button still held from starting the game does not end the demo right away
+ */
+fn end_attract_mode_on_input(
+    // Globals
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    // Events
+    mut cursor_evr: EventReader<CursorMoved>,
+    mut back_evw: EventWriter<BackEvent>,
+    // Queries
+    gamepads: Query<&Gamepad>,
+) {
+    let mouse_moved = cursor_evr.read().count() > 0;
+
+    if !mouse_moved
+        && mouse_input.get_just_pressed().next().is_none()
+        && keyboard_input.get_just_pressed().next().is_none()
+        && !gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    {
+        return;
+    }
+
+    back_evw.write(BackEvent);
+    debug!("Input during attract mode; returning to level selection");
+}
+
+/*
+ * However the demo level ends, the autopilot hands back to the player
+ */
+fn stop_attract_mode(
+    // Globals
+    mut attract_mode: ResMut<AttractMode>,
+    mut autopilot: ResMut<Autopilot>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if !attract_mode.active {
+        return;
+    }
+
+    attract_mode.active = false;
+    attract_mode.idle_time = 0.0;
+    *autopilot = Autopilot::default();
+    // hand the player's generator back, and keep the demo's where it left off for the next demo
+    std::mem::swap(&mut *game_rng, &mut attract_mode.rng);
+    debug!("Attract mode stopped");
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin functions
+ * ================================================================================================================
+ */
+
+/*
+ * Demos go through the levels in order; levels not yet unlocked are skipped when the demo starts
+ */
+fn next_demo_level(demo_level: SelectedLevel) -> SelectedLevel {
+    match demo_level {
+        SelectedLevel::Level1 => SelectedLevel::Level2,
+        SelectedLevel::Level2 => SelectedLevel::Level3,
+        SelectedLevel::Level3 => SelectedLevel::Level4,
+        SelectedLevel::Level4 => SelectedLevel::Level5,
+        SelectedLevel::Level5 => SelectedLevel::Level1,
+    }
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin functions
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Resources
+ * ================================================================================================================
+ */
+
+#[derive(Resource, Default)]
+pub struct AttractMode {
+    // true while the autopilot plays a demo level
+    pub active: bool,
+    idle_time: f32,
+    // level played by the next demo
+    demo_level: SelectedLevel,
+    // random numbers of the demo games, swapped in for the player's while a demo runs
+    rng: GameRng,
+}
+
+/*
+ * ================================================================================================================
+ * END - Resources
+ * ================================================================================================================
+ */
@@ -0,0 +1,315 @@
+/*
+ * Plugin to let the computer play
+ * The autopilot predicts where the ball reaches a paddle (bouncing off the walls on the way) and steers the paddle there
+ * It is an input source writing the same actions as the mouse, so everything else behaves exactly as in a real game
+ * Enable with: cargo run -- --autopilot
+ */
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    GameRng, GameplaySet, PlayerBall, PlayerBallInHold, PlayerPaddle, Playfield,
+    calculate_paddle_rect, calculate_sprite_size, handle_move_paddle_to_events,
+};
+
+use crate::actions::{LaunchPressEvent, LaunchReleaseEvent, MovePaddleToEvent};
+use crate::levels::LevelRules;
+
+/*
+ * Plugin defintion
+ */
+pub fn autopilot_plugin(app: &mut App) {
+    app
+        // Play before the actions are handled, so the autopilot's input is handled in the same step
+        .add_systems(
+            FixedUpdate,
+            drive_autopilot
+                .before(handle_move_paddle_to_events)
+                .in_set(GameplaySet::Input)
+                .run_if(autopilot_enabled),
+        )
+        // Resources
+        .insert_resource(Autopilot {
+            enabled: std::env::args().any(|arg| arg == "--autopilot"),
+            ..Default::default()
+        });
+}
+
+// how long a ball is held before it is launched, in seconds
+const LAUNCH_HOLD_TIME: f32 = 0.3;
+// drag used to aim a launch, in pixels; the sideways part is picked at random for each launch
+const LAUNCH_DRAG: f32 = 60.0;
+const LAUNCH_MAX_SIDEWAYS_DRAG: f32 = 30.0;
+
+/*
+ * ================================================================================================================
+ * START - Plugin Systems
+ * ================================================================================================================
+ */
+
+fn autopilot_enabled(autopilot: Res<Autopilot>) -> bool {
+    autopilot.enabled
+}
+
+/*
+ * Writes the actions a player with a mouse would: pressing on the paddle for a new ball, dragging and releasing to
+ * launch it, and pointing to where the ball will come down
+ */
+fn drive_autopilot(
+    // Singles
+    player_paddle: Single<(&Transform, &Sprite), With<PlayerPaddle>>,
+    // Globals
+    time: Res<Time>,
+    gravity: Res<Gravity>,
+    images: Res<Assets<Image>>,
+    playfield: Res<Playfield>,
+    level_rules: Res<LevelRules>,
+    mut autopilot: ResMut<Autopilot>,
+    // Events
+    mut move_paddle_to_evw: EventWriter<MovePaddleToEvent>,
+    mut launch_press_evw: EventWriter<LaunchPressEvent>,
+    mut launch_release_evw: EventWriter<LaunchReleaseEvent>,
+    // Queries
+    all_player_balls: Query<(), With<PlayerBall>>,
+    held_player_balls: Query<&PlayerBallInHold>,
+    player_balls: Query<
+        (&Transform, &Sprite, &LinearVelocity),
+        (With<PlayerBall>, Without<PlayerBallInHold>),
+    >,
+) {
+    let (paddle_transform, paddle_sprite) = player_paddle.into_inner();
+
+    let mut move_cursor_to = |world_position: Vec2| {
+        move_paddle_to_evw.write(MovePaddleToEvent {
+            position: world_position,
+        });
+    };
+
+    // a held ball is aimed by dragging away from where it was picked up, then launched
+    if let Some(player_ball_in_hold) = held_player_balls.iter().next() {
+        autopilot.hold_time += time.delta_secs();
+        move_cursor_to(player_ball_in_hold.aim_anchor + autopilot.launch_drag);
+
+        if autopilot.hold_time >= LAUNCH_HOLD_TIME {
+            launch_release_evw.write(LaunchReleaseEvent);
+            autopilot.hold_time = 0.0;
+            trace!("Autopilot launched");
+        }
+        return;
+    }
+    autopilot.hold_time = 0.0;
+
+    // no ball in play: press on the paddle to get a new one
+    if all_player_balls.is_empty() {
+        launch_press_evw.write(LaunchPressEvent {
+            pointer: Some(paddle_transform.translation.truncate()),
+        });
+
+        let sideways_drag = (2.0 * autopilot.rng.next_f32() - 1.0) * LAUNCH_MAX_SIDEWAYS_DRAG;
+        autopilot.launch_drag = Vec2::new(sideways_drag, LAUNCH_DRAG);
+        return;
+    }
+
+    // the target is only reconsidered after the reaction delay, and then missed by up to the aim error
+    autopilot.reaction_timer -= time.delta_secs();
+    if autopilot.reaction_timer <= 0.0 {
+        autopilot.reaction_timer = autopilot.skill.reaction_delay;
+
+        let paddle_half_height =
+            0.5 * calculate_paddle_rect(&images, paddle_sprite, paddle_transform).height();
+        // a mirrored paddle at the top catches balls there, and is steered through the player's paddle
+        let top_paddle_mirror = level_rules
+            .mirrored_paddle
+            .filter(|paddle_mirror| paddle_mirror.is_at_top());
+
+        let first_arrival = player_balls
+            .iter()
+            .filter_map(|(ball_transform, ball_sprite, ball_velocity)| {
+                let ball_radius =
+                    0.5 * calculate_sprite_size(&images, ball_sprite).x * ball_transform.scale.x;
+
+                predict_ball_arrival(
+                    ball_transform.translation.truncate(),
+                    ball_velocity.0,
+                    gravity.0,
+                    ball_radius,
+                    paddle_half_height,
+                    top_paddle_mirror.is_some(),
+                    &playfield,
+                )
+            })
+            .min_by(|arrival, other_arrival| arrival.time.total_cmp(&other_arrival.time));
+
+        let aim_error = (2.0 * autopilot.rng.next_f32() - 1.0) * autopilot.skill.aim_error;
+
+        autopilot.target_x = match first_arrival {
+            Some(arrival) => {
+                let arrival_x = match top_paddle_mirror {
+                    // mirroring the x twice brings it back, so the mirror also maps the top paddle's x to the player's
+                    Some(paddle_mirror) if arrival.at_top => paddle_mirror.mirror_x(arrival.x),
+                    _ => arrival.x,
+                };
+                arrival_x + aim_error
+            }
+            // no ball comes towards a paddle any time soon: stay below one
+            None => player_balls
+                .iter()
+                .next()
+                .map_or(paddle_transform.translation.x, |(ball_transform, ..)| {
+                    ball_transform.translation.x
+                }),
+        };
+    }
+
+    move_cursor_to(Vec2::new(autopilot.target_x, playfield.paddle_y()));
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin functions
+ * ================================================================================================================
+ */
+
+/*
+ * Follows the ball's flight under gravity, bouncing off the side walls (and the top wall, unless a paddle guards it),
+ * until it reaches the paddle at the bottom or the mirrored paddle at the top
+ * Elements are ignored; the prediction is corrected each time the target is reconsidered
+ */
+fn predict_ball_arrival(
+    // Parameters
+    mut position: Vec2,
+    mut velocity: Vec2,
+    gravity: Vec2,
+    ball_radius: f32,
+    paddle_half_height: f32,
+    paddle_at_top: bool,
+    // Globals
+    playfield: &Playfield,
+) -> Option<BallArrival> {
+    const TIME_STEP: f32 = 1.0 / 240.0;
+    const MAX_PREDICTION_TIME: f32 = 4.0; // in seconds
+
+    let max_abs_x = playfield.inner_half_width() - ball_radius;
+    let max_y = playfield.inner_half_height() - ball_radius;
+    // where the ball touches the paddle; the top paddle sits mirrored across the playfield's center
+    let arrival_y = playfield.paddle_y() + paddle_half_height + ball_radius;
+    let top_arrival_y = -arrival_y;
+
+    let mut time = 0.0;
+    while time < MAX_PREDICTION_TIME {
+        velocity += gravity * TIME_STEP;
+        position += velocity * TIME_STEP;
+        time += TIME_STEP;
+
+        if f32::abs(position.x) > max_abs_x {
+            position.x = position.x.signum() * (2.0 * max_abs_x - f32::abs(position.x));
+            velocity.x = -velocity.x;
+        }
+
+        if paddle_at_top {
+            if velocity.y > 0.0 && position.y >= top_arrival_y {
+                return Some(BallArrival {
+                    x: position.x,
+                    time,
+                    at_top: true,
+                });
+            }
+        } else if position.y > max_y {
+            position.y = 2.0 * max_y - position.y;
+            velocity.y = -velocity.y;
+        }
+
+        if velocity.y < 0.0 && position.y <= arrival_y {
+            return Some(BallArrival {
+                x: position.x,
+                time,
+                at_top: false,
+            });
+        }
+    }
+
+    None
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin functions
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Resources
+ * ================================================================================================================
+ */
+
+/*
+ * How well the autopilot plays
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct AutopilotSkill {
+    // seconds between two decisions on where to move
+    pub reaction_delay: f32,
+    // largest distance by which the paddle misses the predicted position, in pixels
+    pub aim_error: f32,
+}
+
+impl AutopilotSkill {
+    pub const PERFECT: AutopilotSkill = AutopilotSkill {
+        reaction_delay: 0.0,
+        aim_error: 0.0,
+    };
+
+    pub const CASUAL: AutopilotSkill = AutopilotSkill {
+        reaction_delay: 0.25,
+        aim_error: 40.0,
+    };
+}
+
+#[derive(Resource)]
+pub struct Autopilot {
+    pub enabled: bool,
+    pub skill: AutopilotSkill,
+    target_x: f32,
+    reaction_timer: f32,
+    hold_time: f32,
+    launch_drag: Vec2,
+    // own generator, so the autopilot does not change the game's random outcomes
+    rng: GameRng,
+}
+
+impl Default for Autopilot {
+    fn default() -> Self {
+        Autopilot {
+            enabled: false,
+            skill: AutopilotSkill::PERFECT,
+            target_x: 0.0,
+            reaction_timer: 0.0,
+            hold_time: 0.0,
+            launch_drag: Vec2::new(0.0, LAUNCH_DRAG),
+            rng: GameRng::default(),
+        }
+    }
+}
+
+/*
+ * Where and when a ball reaches a paddle
+ */
+struct BallArrival {
+    x: f32,
+    time: f32,
+    // reached the mirrored paddle at the top instead of the player's paddle
+    at_top: bool,
+}
+
+/*
+ * ================================================================================================================
+ * END - Resources
+ * ================================================================================================================
+ */
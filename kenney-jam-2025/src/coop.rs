@@ -0,0 +1,343 @@
+/*
+ * Plugin to handle local co-op
+ * Player two joins by moving with the keyboard (A/D or the arrow keys) or a second gamepad, and gets a paddle of their own;
+ * both players share the level and its lives
+ * The paddles keep to their halves of the playfield; to let both move everywhere: cargo run -- --coop-overlapping
+ */
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    AddCollider, ColliderType, GameState, GameplaySet, PADDLE_BASE_SCALE_X, PADDLE_MAX_SPEED,
+    PaddleArea, PaddleMotion, PlayerPaddle, Playfield, calculate_paddle_rect,
+    drive_paddle_towards_target, enforce_paddle_borders, paddle_velocity_towards_target,
+};
+
+use crate::actions::{ActionSources, player_one_gamepad};
+use crate::levels::OnLevelsScreen;
+
+/*
+ * Plugin defintion
+ */
+pub fn coop_plugin(app: &mut App) {
+    app
+        // Read player two's input once per frame, like player one's input sources
+        .add_systems(
+            PreUpdate,
+            read_second_player_input
+                .in_set(ActionSources)
+                .run_if(in_state(GameState::Levels)),
+        )
+        // and move their paddle in the fixed schedule, next to player one's
+        .add_systems(
+            FixedUpdate,
+            (join_second_player, move_second_player_paddle)
+                .chain()
+                .after(drive_paddle_towards_target)
+                .in_set(GameplaySet::Input)
+                .run_if(in_state(GameState::Levels)),
+        )
+        // When leaving the level, player two's paddle is despawned with the screen; they join again in the next one
+        .add_systems(OnExit(GameState::Levels), end_coop)
+        // Resources
+        .insert_resource(CoopSettings {
+            paddle_area: if std::env::args().any(|arg| arg == "--coop-overlapping") {
+                CoopPaddleArea::Overlapping
+            } else {
+                CoopPaddleArea::Halves
+            },
+        })
+        .init_resource::<SecondPlayerInput>();
+}
+
+// speed of player two's paddle at full stick deflection (or with a key held), in pixels per second
+const SECOND_PLAYER_PADDLE_SPEED: f32 = 900.0;
+// stick deflections below this are ignored, so a resting stick does not join or drift
+const GAMEPAD_STICK_DEAD_ZONE: f32 = 0.15;
+
+/*
+ * ================================================================================================================
+ * START - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * Combines keyboard and gamepad into one movement axis (-1 = left, 1 = right)
+ */
+fn read_second_player_input(
+    // Globals
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut second_player_input: ResMut<SecondPlayerInput>,
+    // Queries
+    gamepads: Query<(Entity, &Gamepad)>,
+) {
+    let mut axis = 0.0;
+
+    if keyboard_input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        axis -= 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        axis += 1.0;
+    }
+
+    // the first gamepad belongs to player one
+    let player_one_gamepad_entity = player_one_gamepad(&gamepads);
+
+    for (gamepad_entity, gamepad) in gamepads.iter() {
+        if Some(gamepad_entity) == player_one_gamepad_entity {
+            continue;
+        }
+
+        let stick_x = gamepad.left_stick().x;
+        if stick_x.abs() > GAMEPAD_STICK_DEAD_ZONE {
+            axis += stick_x;
+        }
+
+        if gamepad.pressed(GamepadButton::DPadLeft) {
+            axis -= 1.0;
+        }
+        if gamepad.pressed(GamepadButton::DPadRight) {
+            axis += 1.0;
+        }
+    }
+
+    second_player_input.axis = f32::clamp(axis, -1.0, 1.0);
+
+    if second_player_input.axis != 0.0 && !second_player_input.joined {
+        second_player_input.joined = true;
+        debug!("Player two joined");
+    }
+}
+
+/*
+ * Spawns player two's paddle as soon as they join, and splits the playfield between the paddles if configured
+ */
+fn join_second_player(
+    // Singles
+    player_paddle: Single<&mut PaddleMotion, With<PlayerPaddle>>,
+    // Globals
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    coop_settings: Res<CoopSettings>,
+    second_player_input: Res<SecondPlayerInput>,
+    playfield: Res<Playfield>,
+    // Queries
+    second_player_paddles: Query<(), With<SecondPlayerPaddle>>,
+) {
+    if !second_player_input.joined || !second_player_paddles.is_empty() {
+        return;
+    }
+
+    let mut paddle_motion = player_paddle.into_inner();
+
+    let second_player_area = match coop_settings.paddle_area {
+        CoopPaddleArea::Halves => {
+            paddle_motion.area = PaddleArea::LeftHalf;
+            PaddleArea::RightHalf
+        }
+        CoopPaddleArea::Overlapping => PaddleArea::Full,
+    };
+
+    // start in the middle of the right half
+    let start_x = 0.5 * playfield.half_width;
+
+    let mut sprite = Sprite::from_image(asset_server.load("paddleBlu.png"));
+    sprite.color = Color::srgb(0.6, 1.0, 0.6);
+
+    commands.spawn(SecondPlayerPaddleBundle {
+        marker: SecondPlayerPaddle,
+        screen_marker: OnLevelsScreen,
+        add_collider: AddCollider {
+            collider_scale: 1.0,
+            collider_type: ColliderType::Capsule,
+        },
+        sprite,
+        transform: Transform::from_xyz(start_x, playfield.paddle_y(), 0.0).with_scale(Vec3::new(
+            PADDLE_BASE_SCALE_X,
+            1.0,
+            1.0,
+        )),
+        rigid_body: RigidBody::Kinematic,
+        motion: PaddleMotion {
+            target_x: start_x,
+            max_speed: Some(PADDLE_MAX_SPEED),
+            area: second_player_area,
+            touching_balls: Vec::new(),
+        },
+    });
+
+    debug!("Second player paddle spawned");
+}
+
+/*
+ * Player two steers their paddle's target; the paddle is driven there like player one's
+ */
+fn move_second_player_paddle(
+    // Globals
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    images: Res<Assets<Image>>,
+    playfield: Res<Playfield>,
+    second_player_input: Res<SecondPlayerInput>,
+    // Queries
+    mut second_player_paddles: Query<
+        (
+            &mut Transform,
+            &Sprite,
+            &mut PaddleMotion,
+            &mut LinearVelocity,
+        ),
+        (With<SecondPlayerPaddle>, Without<PlayerPaddle>),
+    >,
+) {
+    // the physics step advances by less than a fixed step while in slow motion
+    let physics_delta = time.delta_secs() * physics_time.relative_speed();
+
+    if physics_delta <= 0.0 {
+        return;
+    }
+
+    for (mut paddle_transform, paddle_sprite, mut paddle_motion, mut paddle_velocity) in
+        second_player_paddles.iter_mut()
+    {
+        // follows playfield changes, like player one's paddle in update_playfield_borders
+        if paddle_transform.translation.y != playfield.paddle_y() {
+            paddle_transform.translation.y = playfield.paddle_y();
+        }
+
+        // the target must not run off beyond the borders while a key is held
+        let paddle_half_width =
+            0.5 * calculate_paddle_rect(&images, paddle_sprite, &paddle_transform).width();
+        paddle_motion.target_x = enforce_paddle_borders(
+            paddle_motion.target_x
+                + second_player_input.axis * SECOND_PLAYER_PADDLE_SPEED * time.delta_secs(),
+            &playfield,
+            paddle_half_width,
+            &paddle_motion.area,
+        );
+
+        paddle_velocity.0 = paddle_velocity_towards_target(
+            &paddle_transform,
+            paddle_sprite,
+            &paddle_motion,
+            physics_delta,
+            &images,
+            &playfield,
+        );
+    }
+}
+
+/*
+ * Hands the whole playfield back to player one
+ */
+fn end_coop(
+    // Singles
+    player_paddle: Option<Single<&mut PaddleMotion, With<PlayerPaddle>>>,
+    // Globals
+    mut second_player_input: ResMut<SecondPlayerInput>,
+) {
+    if !second_player_input.joined {
+        return;
+    }
+
+    *second_player_input = SecondPlayerInput::default();
+
+    if let Some(player_paddle) = player_paddle {
+        player_paddle.into_inner().area = PaddleArea::Full;
+    }
+    debug!("Co-op ended");
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Components
+ * ================================================================================================================
+ */
+
+/*
+ * Marks player two's paddle; it is not a PlayerPaddle, since only player one holds and launches balls
+ * Like player one's paddle, it hands its momentum to the ball and ends combos
+ */
+#[derive(Component)]
+struct SecondPlayerPaddle;
+
+/*
+ * ================================================================================================================
+ * END - Plugin Components
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Bundles
+ * ================================================================================================================
+ */
+#[derive(Bundle)]
+struct SecondPlayerPaddleBundle {
+    marker: SecondPlayerPaddle,
+    screen_marker: OnLevelsScreen,
+    add_collider: AddCollider,
+    sprite: Sprite,
+    transform: Transform,
+    rigid_body: RigidBody,
+    motion: PaddleMotion,
+}
+/*
+ * ================================================================================================================
+ * END - Plugin Bundles
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Enumerations
+ * ================================================================================================================
+ */
+
+/*
+ * Whether each paddle keeps to its own half of the playfield, or both may move everywhere
+ */
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CoopPaddleArea {
+    #[default]
+    Halves,
+    Overlapping,
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Enumerations
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Resources
+ * ================================================================================================================
+ */
+
+#[derive(Resource, Default)]
+pub struct CoopSettings {
+    pub paddle_area: CoopPaddleArea,
+}
+
+/*
+ * Player two's movement input of the current frame
+ */
+#[derive(Resource, Default)]
+struct SecondPlayerInput {
+    joined: bool,
+    axis: f32, // -1 = left, 1 = right
+}
+
+/*
+ * ================================================================================================================
+ * END - Resources
+ * ================================================================================================================
+ */
@@ -1,7 +1,10 @@
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
-use super::{AddCollider, ColliderType};
+use super::{
+    AddCollider, ColliderType, PLAYER_BALL_MAX_SPEED, POWER_LEVEL_THRESHOLDS, PlayerBall,
+    PlayerBallInHold, update_power_level,
+};
 
 use crate::levels::OnLevelsScreen;
 
@@ -13,40 +16,939 @@ pub fn spawn_element(
     // Globals
     commands: &mut Commands,
     asset_server: &Res<AssetServer>,
-) {
+) -> Entity {
     let asset_path = assemble_asset_path(&element_durability, &element_shape);
     let collider_type = get_collider_type(&element_shape);
 
     if element_durability == ElementDurability::Indestructible {
-        commands.spawn(IndestructibleElementBundle {
-            marker: IndestructibleElement,
-            screen_marker: OnLevelsScreen,
-            sprite: Sprite::from_image(asset_server.load(asset_path)),
-            transform: Transform::from_xyz(element_position.x, element_position.y, 0.0),
-            add_collider: AddCollider {
-                collider_scale: 1.0,
-                collider_type: collider_type,
-            },
-            rigid_body: RigidBody::Static,
+        commands
+            .spawn(IndestructibleElementBundle {
+                marker: IndestructibleElement,
+                screen_marker: OnLevelsScreen,
+                sprite: Sprite::from_image(asset_server.load(asset_path)),
+                transform: Transform::from_xyz(element_position.x, element_position.y, 0.0),
+                add_collider: AddCollider {
+                    collider_scale: 1.0,
+                    collider_type: collider_type,
+                },
+                rigid_body: RigidBody::Static,
+            })
+            .id()
+    } else {
+        let hit_points = hit_points_for_durability(&element_durability);
+
+        commands
+            .spawn(DestructibleElementBundle {
+                marker: DestructibleElement {
+                    element_durability: element_durability,
+                    element_shape,
+                    hit_points,
+                    max_hit_points: hit_points,
+                    touching_balls: Vec::new(),
+                    tint: Color::WHITE,
+                },
+                screen_marker: OnLevelsScreen,
+                sprite: Sprite::from_image(asset_server.load(asset_path)),
+                transform: Transform::from_xyz(element_position.x, element_position.y, 0.0),
+                add_collider: AddCollider {
+                    collider_scale: 1.0,
+                    collider_type: collider_type,
+                },
+                rigid_body: RigidBody::Static,
+            })
+            .id()
+    }
+}
+
+/*
+ * Spawns a destructible element that explodes when destroyed, damaging every destructible element in its blast
+ */
+pub fn spawn_explosive_element(
+    // Parameters
+    element_durability: ElementDurability,
+    element_shape: ElementShape,
+    element_position: Vec2,
+    blast_radius: f32,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let element = spawn_element(
+        element_durability,
+        element_shape,
+        element_position,
+        commands,
+        asset_server,
+    );
+
+    commands.entity(element).insert((
+        ExplosiveElement { blast_radius },
+        ElementTint(EXPLOSIVE_ELEMENT_TINT),
+    ));
+
+    element
+}
+
+/*
+ * Spawns an element that drains the ball's power on contact
+ */
+pub fn spawn_absorber_element(
+    // Parameters
+    element_durability: ElementDurability,
+    element_shape: ElementShape,
+    element_position: Vec2,
+    power_levels_drained: usize,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let element = spawn_element(
+        element_durability,
+        element_shape,
+        element_position,
+        commands,
+        asset_server,
+    );
+
+    commands.entity(element).insert((
+        AbsorberElement {
+            power_levels_drained,
+            touching_balls: Vec::new(),
+        },
+        ElementTint(ABSORBER_ELEMENT_TINT),
+    ));
+
+    element
+}
+
+/*
+ * Slows down balls that hit an absorber, so they drop by the absorber's number of power levels
+ * Runs before the ball's power level is recalculated, which then lowers the power level and ball color
+ */
+pub fn drain_balls_hitting_absorbers(
+    // Collisions
+    collisions: Collisions,
+    // Queries
+    mut player_balls: Query<(&mut PlayerBall, &mut Sprite, &mut LinearVelocity)>,
+    mut absorbers: Query<(Entity, &mut AbsorberElement), Without<PlayerBall>>,
+) {
+    for (absorber_entity, mut absorber) in absorbers.iter_mut() {
+        // forget balls that no longer touch the absorber, so their next contact counts again
+        absorber.touching_balls.retain(|touching_ball| {
+            collisions.iter().any(|contact_pair| {
+                (contact_pair.collider1.eq(touching_ball)
+                    && contact_pair.collider2.eq(&absorber_entity))
+                    || (contact_pair.collider2.eq(touching_ball)
+                        && contact_pair.collider1.eq(&absorber_entity))
+            })
         });
+
+        for contact_pair in collisions.iter() {
+            let ball_entity;
+            if contact_pair.collider1.eq(&absorber_entity) {
+                ball_entity = contact_pair.collider2;
+            } else if contact_pair.collider2.eq(&absorber_entity) {
+                ball_entity = contact_pair.collider1;
+            } else {
+                continue;
+            }
+
+            let Ok((mut player_ball, mut ball_sprite, mut ball_velocity)) =
+                player_balls.get_mut(ball_entity)
+            else {
+                continue;
+            };
+
+            // still the same contact as in a previous frame
+            if absorber.touching_balls.contains(&ball_entity) {
+                continue;
+            }
+            absorber.touching_balls.push(ball_entity);
+
+            let drained_speed =
+                drained_speed(ball_velocity.length(), absorber.power_levels_drained);
+            ball_velocity.0 = ball_velocity.normalize_or_zero() * drained_speed;
+            update_power_level(drained_speed, &mut player_ball, &mut ball_sprite);
+
+            debug!("Absorber drained the ball to {:?}", drained_speed);
+        }
+    }
+}
+
+/*
+ * Speed in the middle of the power level that lies the given number of levels below the current speed's
+ * Never speeds the ball up, even when it already is at the lowest power level
+ */
+fn drained_speed(speed: f32, power_levels_drained: usize) -> f32 {
+    let power_level = POWER_LEVEL_THRESHOLDS
+        .iter()
+        .filter(|threshold| speed >= **threshold)
+        .count();
+    let drained_power_level = power_level.saturating_sub(power_levels_drained);
+
+    let level_start = if drained_power_level == 0 {
+        0.0
     } else {
-        commands.spawn(DestructibleElementBundle {
-            marker: DestructibleElement {
-                element_durability: element_durability,
+        POWER_LEVEL_THRESHOLDS[drained_power_level - 1]
+    };
+    let level_end = POWER_LEVEL_THRESHOLDS
+        .get(drained_power_level)
+        .copied()
+        .unwrap_or(PLAYER_BALL_MAX_SPEED);
+
+    speed.min(0.5 * (level_start + level_end))
+}
+
+/*
+ * Spawns a switch that toggles all gates sharing its link id
+ * Indestructible switches toggle with every hit, destructible ones once, with the hit that destroys them
+ */
+pub fn spawn_switch(
+    // Parameters
+    element_durability: ElementDurability,
+    element_shape: ElementShape,
+    element_position: Vec2,
+    link_id: u32,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let element = spawn_element(
+        element_durability,
+        element_shape,
+        element_position,
+        commands,
+        asset_server,
+    );
+
+    commands.entity(element).insert((
+        Switch {
+            link_id,
+            touching_balls: Vec::new(),
+        },
+        ElementTint(SWITCH_ELEMENT_TINT),
+    ));
+
+    element
+}
+
+/*
+ * Spawns an indestructible gate that changes when a switch with the same link id is toggled
+ */
+pub fn spawn_gate(
+    // Parameters
+    element_shape: ElementShape,
+    element_position: Vec2,
+    link_id: u32,
+    gate_action: GateAction,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let element = spawn_element(
+        ElementDurability::Indestructible,
+        element_shape,
+        element_position,
+        commands,
+        asset_server,
+    );
+
+    commands.entity(element).insert(Gate {
+        link_id,
+        action: gate_action,
+        element_shape,
+        open: false,
+    });
+
+    element
+}
+
+/*
+ * Opens all closed gates with the given link id, and closes all open ones
+ */
+pub fn toggle_gates(
+    // Parameters
+    link_id: u32,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    // Queries
+    gates: &mut Query<(Entity, &mut Gate, &mut Transform, &mut Visibility)>,
+) {
+    for (gate_entity, mut gate, mut gate_transform, mut gate_visibility) in gates.iter_mut() {
+        if gate.link_id != link_id {
+            continue;
+        }
+
+        gate.open = !gate.open;
+        debug!("Gate {:?} open: {}", gate_entity, gate.open);
+
+        match gate.action {
+            GateAction::Disappear => {
+                if gate.open {
+                    commands.entity(gate_entity).insert(ColliderDisabled);
+                    *gate_visibility = Visibility::Hidden;
+                } else {
+                    commands.entity(gate_entity).remove::<ColliderDisabled>();
+                    *gate_visibility = Visibility::Inherited;
+                }
+            }
+            GateAction::Slide { offset } => {
+                let slide = if gate.open { offset } else { -offset };
+                gate_transform.translation += slide.extend(0.0);
+            }
+            GateAction::BecomeDestructible { element_durability } => {
+                // there is no way back: the gate is now a regular element
+                let element_shape = gate.element_shape;
+                let hit_points = hit_points_for_durability(&element_durability);
+
+                commands
+                    .entity(gate_entity)
+                    .remove::<(Gate, IndestructibleElement)>()
+                    .insert((
+                        DestructibleElement {
+                            element_durability,
+                            element_shape,
+                            hit_points,
+                            max_hit_points: hit_points,
+                            touching_balls: Vec::new(),
+                            tint: Color::WHITE,
+                        },
+                        Sprite::from_image(
+                            asset_server
+                                .load(assemble_asset_path(&element_durability, &element_shape)),
+                        ),
+                    ));
+            }
+        }
+    }
+}
+
+/*
+ * Colors freshly spawned special elements, so they stand out from the regular ones
+ */
+pub fn apply_element_tints(
+    // Queries
+    mut tinted_elements: Query<
+        (&ElementTint, &mut Sprite, Option<&mut DestructibleElement>),
+        Added<ElementTint>,
+    >,
+) {
+    for (element_tint, mut sprite, element) in tinted_elements.iter_mut() {
+        sprite.color = element_tint.0;
+
+        // damaged elements darken from their tint instead of from white
+        if let Some(mut element) = element {
+            element.tint = element_tint.0;
+        }
+    }
+}
+
+/*
+ * Spawns an indestructible bumper that kicks the ball away on contact
+ */
+pub fn spawn_bumper(
+    // Parameters
+    element_shape: ElementShape,
+    element_position: Vec2,
+    bumper_kick: BumperKick,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let element = spawn_element(
+        ElementDurability::Indestructible,
+        element_shape,
+        element_position,
+        commands,
+        asset_server,
+    );
+
+    commands.entity(element).insert(Bumper {
+        kick: bumper_kick,
+        remaining_cooldown: 0.0,
+        touching_balls: Vec::new(),
+    });
+
+    element
+}
+
+/*
+ * Kicks balls that hit a bumper away from its center
+ * Runs before the ball's power level is recalculated, so a kick can push the ball over the next threshold
+ */
+pub fn kick_balls_off_bumpers(
+    // Globals
+    time: Res<Time>,
+    // Collisions
+    collisions: Collisions,
+    // Queries
+    mut player_balls: Query<(&Transform, &mut LinearVelocity), With<PlayerBall>>,
+    mut bumpers: Query<(Entity, &mut Bumper, &Transform, &mut Sprite), Without<PlayerBall>>,
+) {
+    for (bumper_entity, mut bumper, bumper_transform, mut bumper_sprite) in bumpers.iter_mut() {
+        bumper.remaining_cooldown = (bumper.remaining_cooldown - time.delta_secs()).max(0.0);
+
+        // forget balls that no longer touch the bumper, so their next contact counts again
+        bumper.touching_balls.retain(|touching_ball| {
+            collisions.iter().any(|contact_pair| {
+                (contact_pair.collider1.eq(touching_ball)
+                    && contact_pair.collider2.eq(&bumper_entity))
+                    || (contact_pair.collider2.eq(touching_ball)
+                        && contact_pair.collider1.eq(&bumper_entity))
+            })
+        });
+
+        for contact_pair in collisions.iter() {
+            let ball_entity;
+            if contact_pair.collider1.eq(&bumper_entity) {
+                ball_entity = contact_pair.collider2;
+            } else if contact_pair.collider2.eq(&bumper_entity) {
+                ball_entity = contact_pair.collider1;
+            } else {
+                continue;
+            }
+
+            let Ok((ball_transform, mut ball_velocity)) = player_balls.get_mut(ball_entity) else {
+                continue;
+            };
+
+            // still the same contact as in a previous frame
+            if bumper.touching_balls.contains(&ball_entity) {
+                continue;
+            }
+            bumper.touching_balls.push(ball_entity);
+
+            if bumper.remaining_cooldown > 0.0 {
+                trace!("Bumper still cooling down, no kick");
+                continue;
+            }
+
+            let kick_direction = (ball_transform.translation.truncate()
+                - bumper_transform.translation.truncate())
+            .normalize_or(ball_velocity.normalize_or_zero());
+
+            ball_velocity.0 += bumper.kick.kick_speed * kick_direction;
+            bumper.remaining_cooldown = bumper.kick.cooldown;
+
+            debug!("Bumper kicked the ball to {:?}", ball_velocity.length());
+        }
+
+        // bumpers light up while they are ready to kick
+        bumper_sprite.color = if bumper.remaining_cooldown > 0.0 {
+            Color::srgb(0.6, 0.6, 0.6)
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+/*
+ * Spawns two linked portals; a ball entering one leaves the other with the same speed
+ * Portals have no collider, the ball passes through them
+ */
+pub fn spawn_portal_pair(
+    // Parameters
+    first_position: Vec2,
+    second_position: Vec2,
+    exit_direction: PortalDirection,
+    pair_color: Color,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> (Entity, Entity) {
+    let first_portal = commands.spawn_empty().id();
+    let second_portal = commands.spawn_empty().id();
+
+    for (portal, position, linked_portal) in [
+        (first_portal, first_position, second_portal),
+        (second_portal, second_position, first_portal),
+    ] {
+        let mut sprite = Sprite::from_image(asset_server.load("selectorA.png"));
+        sprite.custom_size = Some(Vec2::splat(PORTAL_SIZE));
+        sprite.color = pair_color;
+
+        commands.entity(portal).insert(PortalBundle {
+            marker: Portal {
+                linked_portal,
+                exit_direction,
+                arrived_balls: Vec::new(),
             },
             screen_marker: OnLevelsScreen,
-            sprite: Sprite::from_image(asset_server.load(asset_path)),
-            transform: Transform::from_xyz(element_position.x, element_position.y, 0.0),
-            add_collider: AddCollider {
-                collider_scale: 1.0,
-                collider_type: collider_type,
-            },
-            rigid_body: RigidBody::Static,
+            sprite,
+            transform: Transform::from_xyz(position.x, position.y, 0.0),
         });
     }
+
+    (first_portal, second_portal)
 }
 
-fn get_collider_type(element_shape: &ElementShape) -> ColliderType {
+/*
+ * Spawns a portal pair and its mirror image on the other side of the playfield's center line
+ */
+pub fn spawn_mirrored_portal_pairs(
+    // Parameters
+    first_position: Vec2,
+    second_position: Vec2,
+    exit_direction: PortalDirection,
+    pair_color: Color,
+    // Globals
+    mut commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) {
+    spawn_portal_pair(
+        first_position,
+        second_position,
+        exit_direction,
+        pair_color,
+        &mut commands,
+        &asset_server,
+    );
+
+    spawn_portal_pair(
+        first_position.with_x(-first_position.x),
+        second_position.with_x(-second_position.x),
+        exit_direction.mirrored(),
+        pair_color,
+        &mut commands,
+        &asset_server,
+    );
+}
+
+/*
+ * Moves balls that entered a portal to its linked portal
+ * Only the direction may change, the speed (and with it the power level) is kept
+ * A fast ball covers about the capture radius within one physics step, so the whole path of the step is checked,
+ * not just where the ball ended up
+ */
+pub fn teleport_balls_through_portals(
+    // Globals
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    // Queries
+    mut player_balls: Query<
+        (Entity, &mut Transform, &mut LinearVelocity),
+        (With<PlayerBall>, Without<PlayerBallInHold>, Without<Portal>),
+    >,
+    mut portals: Query<(Entity, &mut Portal, &Transform), Without<PlayerBall>>,
+) {
+    // distance from the portal's center at which a ball is pulled in
+    const PORTAL_CAPTURE_RADIUS: f32 = 0.35 * PORTAL_SIZE;
+
+    // the physics step advances by less than a fixed step while in slow motion
+    let physics_delta = time.delta_secs() * physics_time.relative_speed();
+
+    let swept_path = |ball_transform: &Transform, ball_velocity: &LinearVelocity| {
+        let ball_position = ball_transform.translation.truncate();
+        (
+            ball_position - ball_velocity.0 * physics_delta,
+            ball_position,
+        )
+    };
+
+    // a ball that arrived at a portal is only sent back once it left the portal
+    for (_portal_entity, mut portal, portal_transform) in portals.iter_mut() {
+        portal.arrived_balls.retain(|arrived_ball| {
+            player_balls.get(*arrived_ball).is_ok_and(
+                |(_ball_entity, ball_transform, ball_velocity)| {
+                    let (path_start, path_end) = swept_path(ball_transform, ball_velocity);
+
+                    distance_to_path(
+                        portal_transform.translation.truncate(),
+                        path_start,
+                        path_end,
+                    ) <= PORTAL_CAPTURE_RADIUS
+                },
+            )
+        });
+    }
+
+    for (ball_entity, mut ball_transform, mut ball_velocity) in player_balls.iter_mut() {
+        let (path_start, path_end) = swept_path(&ball_transform, &ball_velocity);
+
+        let Some((entered_portal, linked_portal, exit_direction)) = portals
+            .iter()
+            .find(|(_portal_entity, portal, portal_transform)| {
+                !portal.arrived_balls.contains(&ball_entity)
+                    && distance_to_path(
+                        portal_transform.translation.truncate(),
+                        path_start,
+                        path_end,
+                    ) <= PORTAL_CAPTURE_RADIUS
+            })
+            .map(|(portal_entity, portal, _portal_transform)| {
+                (portal_entity, portal.linked_portal, portal.exit_direction)
+            })
+        else {
+            continue;
+        };
+
+        let Ok((_linked_portal_entity, mut exit_portal, exit_portal_transform)) =
+            portals.get_mut(linked_portal)
+        else {
+            warn!("Portal {:?} is not linked to a portal", entered_portal);
+            continue;
+        };
+
+        exit_portal.arrived_balls.push(ball_entity);
+        ball_transform.translation = exit_portal_transform
+            .translation
+            .truncate()
+            .extend(ball_transform.translation.z);
+        ball_velocity.0 = exit_direction.remap(ball_velocity.0);
+
+        debug!("Ball teleported through portal {:?}", entered_portal);
+    }
+}
+
+/*
+ * Shortest distance from a point to the straight path between two positions
+ */
+fn distance_to_path(point: Vec2, path_start: Vec2, path_end: Vec2) -> f32 {
+    let path = path_end - path_start;
+
+    if path.length_squared() <= f32::EPSILON {
+        return point.distance(path_start);
+    }
+
+    let progress = ((point - path_start).dot(path) / path.length_squared()).clamp(0.0, 1.0);
+    point.distance(path_start + progress * path)
+}
+
+/*
+ * Spawns an element that moves along a path around the given origin
+ * The element is kinematic, so the ball bounces off it taking the element's velocity into account
+ */
+pub fn spawn_moving_element(
+    // Parameters
+    element_durability: ElementDurability,
+    element_shape: ElementShape,
+    path_origin: Vec2,
+    element_path: ElementPath,
+    path_motion: PathMotion,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let moving_element = MovingElement {
+        path: element_path,
+        motion: path_motion,
+        origin: path_origin,
+        elapsed: 0.0,
+    };
+
+    let element = spawn_element(
+        element_durability,
+        element_shape,
+        moving_element.position_at(0.0),
+        commands,
+        asset_server,
+    );
+
+    commands
+        .entity(element)
+        .insert((RigidBody::Kinematic, LinearVelocity::ZERO, moving_element));
+
+    element
+}
+
+/*
+ * Spawns a moving element and its mirror image on the other side of the playfield's center line
+ * Both move in sync, mirrored horizontally, so symmetric layouts stay symmetric while moving
+ */
+pub fn spawn_mirrored_moving_elements(
+    // Parameters
+    element_durability: ElementDurability,
+    element_shape: ElementShape,
+    path_origin: Vec2,
+    element_path: ElementPath,
+    path_motion: PathMotion,
+    // Globals
+    mut commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) {
+    spawn_moving_element(
+        element_durability,
+        element_shape,
+        path_origin,
+        element_path,
+        path_motion,
+        &mut commands,
+        &asset_server,
+    );
+
+    spawn_moving_element(
+        element_durability,
+        element_shape,
+        path_origin.with_x(-path_origin.x),
+        element_path,
+        PathMotion {
+            mirrored: !path_motion.mirrored,
+            ..path_motion
+        },
+        &mut commands,
+        &asset_server,
+    );
+}
+
+/*
+ * Drives the moving elements along their paths
+ * Kinematic bodies are moved through their velocity (not their transform), so the physics step
+ * knows how fast they move when the ball hits them
+ */
+pub fn move_elements_on_paths(
+    // Globals
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    // Queries
+    mut moving_elements: Query<(&mut MovingElement, &mut Transform, &mut LinearVelocity)>,
+) {
+    // jumps further than this (e.g. at the end of a linear path) are teleports instead of movement
+    const TELEPORT_DISTANCE_FACTOR: f32 = 2.0;
+
+    // the physics step advances by less than a fixed step while in slow motion
+    let delta = time.delta_secs() * physics_time.relative_speed();
+
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (mut moving_element, mut transform, mut linear_velocity) in moving_elements.iter_mut() {
+        moving_element.elapsed += delta;
+
+        let current_position = transform.translation.truncate();
+        let next_position = moving_element.position_at(moving_element.elapsed);
+        let step_distance = next_position.distance(current_position);
+
+        if step_distance > TELEPORT_DISTANCE_FACTOR * moving_element.motion.speed * delta {
+            trace!("Moving element jumps to the start of its path");
+
+            transform.translation = next_position.extend(transform.translation.z);
+            linear_velocity.0 = Vec2::ZERO;
+        } else {
+            // also corrects the drift between the path and the simulated position
+            linear_velocity.0 = (next_position - current_position) / delta;
+        }
+    }
+}
+
+/*
+ * Spawns an element that rotates around a pivot, given relative to the element's center
+ * The element is kinematic, so it pushes the ball away when it swings into it
+ */
+pub fn spawn_spinning_element(
+    // Parameters
+    element_durability: ElementDurability,
+    element_shape: ElementShape,
+    pivot: Vec2,
+    pivot_offset: Vec2,
+    spin_motion: SpinMotion,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let element = spawn_element(
+        element_durability,
+        element_shape,
+        pivot - pivot_offset,
+        commands,
+        asset_server,
+    );
+
+    commands.entity(element).insert((
+        RigidBody::Kinematic,
+        LinearVelocity::ZERO,
+        AngularVelocity::ZERO,
+        SpinningElement {
+            motion: spin_motion,
+            pivot,
+            pivot_offset,
+            elapsed: 0.0,
+            touching_balls: Vec::new(),
+        },
+    ));
+
+    element
+}
+
+/*
+ * Turns the spinning elements around their pivots
+ * Elements whose pivot is not their center also need a linear velocity to circle around the pivot
+ */
+pub fn rotate_spinning_elements(
+    // Globals
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    // Queries
+    mut spinning_elements: Query<(
+        &mut SpinningElement,
+        &Transform,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+) {
+    // the physics step advances by less than a fixed step while in slow motion
+    let delta = time.delta_secs() * physics_time.relative_speed();
+
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (mut spinning_element, transform, mut linear_velocity, mut angular_velocity) in
+        spinning_elements.iter_mut()
+    {
+        spinning_element.elapsed += delta;
+        let next_angle = spinning_element.motion.angle_at(spinning_element.elapsed);
+
+        let next_position = spinning_element.pivot
+            - Vec2::from_angle(next_angle).rotate(spinning_element.pivot_offset);
+
+        // measured from the simulated rotation (wrapped to the shorter way round), so any drift
+        // between the element and its motion is corrected with the next step
+        let angle_step = (Quat::from_rotation_z(next_angle) * transform.rotation.inverse())
+            .to_euler(EulerRot::XYZ)
+            .2;
+
+        angular_velocity.0 = angle_step / delta;
+        linear_velocity.0 = (next_position - transform.translation.truncate()) / delta;
+    }
+}
+
+/*
+ * The solver only reflects the ball off the spinner's surface, and without friction the sideways
+ * movement of the surface would be lost; hand it to the ball when it hits a spinner
+ * Runs before the ball's power level is recalculated, so a fast spinner can power up the ball
+ */
+pub fn transfer_spinner_velocity(
+    // Collisions
+    collisions: Collisions,
+    // Queries
+    mut player_balls: Query<(&Transform, &mut LinearVelocity), With<PlayerBall>>,
+    mut spinning_elements: Query<
+        (
+            Entity,
+            &mut SpinningElement,
+            &Transform,
+            &LinearVelocity,
+            &AngularVelocity,
+        ),
+        Without<PlayerBall>,
+    >,
+) {
+    // share of the spinner's sideways surface velocity the ball picks up
+    const TANGENTIAL_VELOCITY_TRANSFER: f32 = 0.8;
+
+    for (
+        spinner_entity,
+        mut spinning_element,
+        spinner_transform,
+        spinner_linear_velocity,
+        spinner_angular_velocity,
+    ) in spinning_elements.iter_mut()
+    {
+        // forget balls that no longer touch the spinner, so their next contact counts again
+        spinning_element.touching_balls.retain(|touching_ball| {
+            collisions.iter().any(|contact_pair| {
+                (contact_pair.collider1.eq(touching_ball)
+                    && contact_pair.collider2.eq(&spinner_entity))
+                    || (contact_pair.collider2.eq(touching_ball)
+                        && contact_pair.collider1.eq(&spinner_entity))
+            })
+        });
+
+        for contact_pair in collisions.iter() {
+            let ball_entity;
+            if contact_pair.collider1.eq(&spinner_entity) {
+                ball_entity = contact_pair.collider2;
+            } else if contact_pair.collider2.eq(&spinner_entity) {
+                ball_entity = contact_pair.collider1;
+            } else {
+                continue;
+            }
+
+            let Ok((ball_transform, mut ball_velocity)) = player_balls.get_mut(ball_entity) else {
+                continue;
+            };
+
+            // still the same contact as in a previous frame
+            if spinning_element.touching_balls.contains(&ball_entity) {
+                continue;
+            }
+            spinning_element.touching_balls.push(ball_entity);
+
+            let Some(contact_normal) = contact_pair
+                .manifolds
+                .first()
+                .map(|manifold| manifold.normal)
+            else {
+                continue;
+            };
+
+            // velocity of the spinner's surface where the ball hit it
+            let lever_arm =
+                ball_transform.translation.truncate() - spinner_transform.translation.truncate();
+            let surface_velocity =
+                spinner_linear_velocity.0 + spinner_angular_velocity.0 * lever_arm.perp();
+            let tangential_velocity =
+                surface_velocity - surface_velocity.dot(contact_normal) * contact_normal;
+
+            ball_velocity.0 += TANGENTIAL_VELOCITY_TRANSFER * tangential_velocity;
+
+            trace!(
+                "Spinner hands {:?} of its surface velocity to the ball",
+                tangential_velocity.length()
+            );
+        }
+    }
+}
+
+/*
+ * Updates the sprite of a damaged element: polygons switch to their glossy variant at half hit points,
+ * all shapes darken as hit points drop
+ */
+pub fn update_damaged_element_sprite(
+    // Parameters
+    element: &DestructibleElement,
+    sprite: &mut Sprite,
+    // Globals
+    asset_server: &Res<AssetServer>,
+) {
+    let hit_point_fraction = element.hit_points as f32 / element.max_hit_points as f32;
+
+    // from half the hit points on, elements with a cracked sprite show it
+    if let Some(damaged_asset_path) =
+        assemble_damaged_asset_path(&element.element_durability, &element.element_shape)
+            .filter(|_| hit_point_fraction <= 0.5)
+    {
+        sprite.image = asset_server.load(damaged_asset_path);
+    }
+
+    let brightness = 0.5 + 0.5 * hit_point_fraction;
+    let tint = element.tint.to_srgba();
+    sprite.color = Color::srgb(
+        brightness * tint.red,
+        brightness * tint.green,
+        brightness * tint.blue,
+    );
+}
+
+/*
+ * Stronger elements take more hits to chip away
+ */
+fn hit_points_for_durability(element_durability: &ElementDurability) -> u32 {
+    const HIT_POINTS_PER_DURABILITY: u32 = 2;
+
+    match element_durability {
+        ElementDurability::Lowest => HIT_POINTS_PER_DURABILITY,
+        ElementDurability::Low => 2 * HIT_POINTS_PER_DURABILITY,
+        ElementDurability::Medium => 3 * HIT_POINTS_PER_DURABILITY,
+        ElementDurability::High => 4 * HIT_POINTS_PER_DURABILITY,
+        ElementDurability::Highest => 5 * HIT_POINTS_PER_DURABILITY,
+        ElementDurability::Indestructible => 0,
+    }
+}
+
+pub fn get_collider_type(element_shape: &ElementShape) -> ColliderType {
     match element_shape {
         ElementShape::Square => ColliderType::Rectangle,
         ElementShape::Diamond => ColliderType::Diamond,
@@ -55,7 +957,7 @@ fn get_collider_type(element_shape: &ElementShape) -> ColliderType {
     }
 }
 
-fn assemble_asset_path(
+pub fn assemble_asset_path(
     element_durability: &ElementDurability,
     element_shape: &ElementShape,
 ) -> String {
@@ -104,6 +1006,21 @@ fn assemble_asset_path(
     asset_path
 }
 
+/*
+ * Only the polygons have a glossy variant in the asset pack (apart from purple, which is always glossy)
+ */
+fn assemble_damaged_asset_path(
+    element_durability: &ElementDurability,
+    element_shape: &ElementShape,
+) -> Option<String> {
+    if *element_shape == ElementShape::Pentagon {
+        Some(assemble_asset_path(element_durability, element_shape).replace(".png", "_glossy.png"))
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
 pub enum ElementShape {
     Square,
     Rectangle,
@@ -111,7 +1028,7 @@ pub enum ElementShape {
     Pentagon,
 }
 
-#[derive(Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum ElementDurability {
     Lowest,
     Low,
@@ -121,9 +1038,294 @@ pub enum ElementDurability {
     Indestructible,
 }
 
+/*
+ * Shape of the path a moving element follows, relative to its origin
+ */
+#[derive(Clone, Copy, Debug)]
+pub enum ElementPath {
+    // moves from the origin to origin + offset, then starts over at the origin
+    Linear { offset: Vec2 },
+    // moves back and forth between the origin and origin + offset
+    PingPong { offset: Vec2 },
+    // circles counter-clockwise around the origin, starting to its right
+    Circular { radius: f32 },
+}
+
+impl ElementPath {
+    // distance travelled during one full cycle of the path
+    fn cycle_length(&self) -> f32 {
+        match self {
+            ElementPath::Linear { offset } => offset.length(),
+            ElementPath::PingPong { offset } => 2.0 * offset.length(),
+            ElementPath::Circular { radius } => core::f32::consts::TAU * radius,
+        }
+    }
+
+    // position relative to the origin, at a fraction (0 to 1) of the cycle
+    fn displacement(&self, cycle_fraction: f32) -> Vec2 {
+        match self {
+            ElementPath::Linear { offset } => *offset * cycle_fraction,
+            ElementPath::PingPong { offset } => {
+                *offset * (1.0 - (2.0 * cycle_fraction - 1.0).abs())
+            }
+            ElementPath::Circular { radius } => {
+                *radius * Vec2::from_angle(core::f32::consts::TAU * cycle_fraction)
+            }
+        }
+    }
+}
+
+/*
+ * How a moving element travels along its path
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct PathMotion {
+    // in pixels per second along the path
+    pub speed: f32,
+    // fraction (0 to 1) of the cycle the element starts at, to offset elements sharing a path
+    pub phase: f32,
+    // mirrors the movement horizontally (circular paths then run clockwise)
+    pub mirrored: bool,
+}
+
+impl Default for PathMotion {
+    fn default() -> Self {
+        PathMotion {
+            speed: 100.0,
+            phase: 0.0,
+            mirrored: false,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct MovingElement {
+    path: ElementPath,
+    motion: PathMotion,
+    origin: Vec2,
+    // time the element has been moving, so the movement is deterministic
+    elapsed: f32,
+}
+
+impl MovingElement {
+    fn position_at(&self, elapsed: f32) -> Vec2 {
+        let cycle_length = self.path.cycle_length();
+
+        if cycle_length <= 0.0 {
+            return self.origin;
+        }
+
+        let cycle_fraction =
+            (elapsed * self.motion.speed / cycle_length + self.motion.phase).rem_euclid(1.0);
+        let mut displacement = self.path.displacement(cycle_fraction);
+
+        if self.motion.mirrored {
+            displacement.x = -displacement.x;
+        }
+
+        self.origin + displacement
+    }
+}
+
+/*
+ * How a spinning element turns around its pivot
+ */
+#[derive(Clone, Copy, Debug)]
+pub enum SpinMotion {
+    // rotates continuously, in radians per second (positive is counter-clockwise)
+    Continuous { angular_speed: f32 },
+    // swings back and forth by up to swing_angle (in radians) to either side, once per swing_period seconds
+    Flipper { swing_angle: f32, swing_period: f32 },
+}
+
+impl SpinMotion {
+    fn angle_at(&self, elapsed: f32) -> f32 {
+        match self {
+            SpinMotion::Continuous { angular_speed } => angular_speed * elapsed,
+            SpinMotion::Flipper {
+                swing_angle,
+                swing_period,
+            } => {
+                if *swing_period <= 0.0 {
+                    return 0.0;
+                }
+
+                swing_angle * (core::f32::consts::TAU * elapsed / swing_period).sin()
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SpinningElement {
+    motion: SpinMotion,
+    pivot: Vec2,
+    // position of the pivot relative to the element's center, before any rotation
+    pivot_offset: Vec2,
+    // time the element has been spinning, so the rotation is deterministic
+    elapsed: f32,
+    // balls currently in contact; a ball only picks up the spinner's velocity once per contact
+    touching_balls: Vec<Entity>,
+}
+
+/*
+ * How hard a bumper kicks the ball, and how often
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct BumperKick {
+    // speed added to the ball, in pixels per second
+    pub kick_speed: f32,
+    // time after a kick during which the bumper does not kick again, in seconds
+    pub cooldown: f32,
+}
+
+impl Default for BumperKick {
+    fn default() -> Self {
+        BumperKick {
+            kick_speed: 150.0,
+            cooldown: 0.5,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Bumper {
+    kick: BumperKick,
+    remaining_cooldown: f32,
+    // balls currently in contact; a ball is only kicked once per contact
+    touching_balls: Vec<Entity>,
+}
+
+// size of the portal sprites, in pixels
+const PORTAL_SIZE: f32 = 48.0;
+
+/*
+ * Direction a ball leaves a portal in
+ */
+#[derive(Clone, Copy, Debug)]
+pub enum PortalDirection {
+    // keeps flying in the direction it entered with
+    Keep,
+    // turns by the given angle, in radians (positive is counter-clockwise)
+    Rotate { angle: f32 },
+    // always leaves in the given direction
+    Fixed { direction: Vec2 },
+}
+
+impl PortalDirection {
+    fn remap(&self, velocity: Vec2) -> Vec2 {
+        match self {
+            PortalDirection::Keep => velocity,
+            PortalDirection::Rotate { angle } => Vec2::from_angle(*angle).rotate(velocity),
+            PortalDirection::Fixed { direction } => {
+                direction.normalize_or(velocity.normalize_or_zero()) * velocity.length()
+            }
+        }
+    }
+
+    // the same remapping, seen in a mirror along the playfield's center line
+    fn mirrored(&self) -> PortalDirection {
+        match self {
+            PortalDirection::Keep => PortalDirection::Keep,
+            PortalDirection::Rotate { angle } => PortalDirection::Rotate { angle: -angle },
+            PortalDirection::Fixed { direction } => PortalDirection::Fixed {
+                direction: direction.with_x(-direction.x),
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Portal {
+    linked_portal: Entity,
+    exit_direction: PortalDirection,
+    // balls that came out of this portal and have not left it yet; they must not be sent straight back
+    arrived_balls: Vec<Entity>,
+}
+
+#[derive(Bundle)]
+struct PortalBundle {
+    marker: Portal,
+    screen_marker: OnLevelsScreen,
+    sprite: Sprite,
+    transform: Transform,
+}
+
 #[derive(Component)]
 pub struct DestructibleElement {
     pub element_durability: ElementDurability,
+    pub element_shape: ElementShape,
+    pub hit_points: u32,
+    pub max_hit_points: u32,
+    // balls currently in contact; a ball only deals damage once per contact
+    pub touching_balls: Vec<Entity>,
+    // color of the undamaged sprite, darkened as hit points drop
+    pub tint: Color,
+}
+
+// orange for explosives, dark blue for absorbers, light yellow for switches
+const EXPLOSIVE_ELEMENT_TINT: Color = Color::srgb(1.0, 0.6, 0.3);
+const ABSORBER_ELEMENT_TINT: Color = Color::srgb(0.4, 0.4, 0.9);
+const SWITCH_ELEMENT_TINT: Color = Color::srgb(1.0, 1.0, 0.5);
+
+/*
+ * Toggles the gates sharing its link id when hit (or destroyed)
+ */
+#[derive(Component)]
+pub struct Switch {
+    pub link_id: u32,
+    // balls currently in contact; a ball only toggles the switch once per contact
+    pub touching_balls: Vec<Entity>,
+}
+
+/*
+ * What happens to a gate when its switch is toggled
+ */
+#[derive(Clone, Copy, Debug)]
+pub enum GateAction {
+    // the gate vanishes, and comes back when toggled again
+    Disappear,
+    // the gate moves by the offset (e.g. a wall opening), and moves back when toggled again
+    Slide {
+        offset: Vec2,
+    },
+    // the gate turns into a regular destructible element, for good
+    BecomeDestructible {
+        element_durability: ElementDurability,
+    },
+}
+
+#[derive(Component)]
+pub struct Gate {
+    link_id: u32,
+    action: GateAction,
+    // kept for the destructible element a gate may turn into
+    element_shape: ElementShape,
+    open: bool,
+}
+
+/*
+ * Color a special element is drawn in, instead of its plain sprite
+ */
+#[derive(Component)]
+pub struct ElementTint(Color);
+
+/*
+ * Drains the ball's power on contact
+ */
+#[derive(Component)]
+pub struct AbsorberElement {
+    power_levels_drained: usize,
+    // balls currently in contact; a ball is only drained once per contact
+    touching_balls: Vec<Entity>,
+}
+
+/*
+ * Destroying this element also damages every destructible element within the blast radius
+ */
+#[derive(Component)]
+pub struct ExplosiveElement {
+    pub blast_radius: f32,
 }
 
 #[derive(Component)]
@@ -1,15 +1,29 @@
 /*
  * Plugin to handle level setup & events
  */
+use std::collections::VecDeque;
+
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
-use super::{BallDestroyedEvent, GameState, PlayerBall, RightMousePressEvent, despawn_screen};
+use super::{
+    BallDestroyedEvent, GameState, GameplaySet, LifeLostEvent, PlayerBall, despawn_screen,
+};
 
+use crate::actions::BackEvent;
+use crate::attract_mode::AttractMode;
+use crate::mirrored_paddle::PaddleMirror;
 use crate::selection::LevelSelectedEvent;
 
-use crate::level_elements::{DestructibleElement, ElementDurability};
-use crate::{Player, PowerLevel};
+use crate::level_elements::{
+    DestructibleElement, ElementDurability, ExplosiveElement, Gate, Switch, apply_element_tints,
+    drain_balls_hitting_absorbers, kick_balls_off_bumpers, move_elements_on_paths,
+    rotate_spinning_elements, teleport_balls_through_portals, toggle_gates,
+    transfer_spinner_velocity, update_damaged_element_sprite,
+};
+use crate::{
+    PhysicsRules, Player, Playfield, PowerLevel, apply_physics_rules, handle_collision_player_ball,
+};
 
 mod level_1;
 mod level_2;
@@ -24,88 +38,259 @@ pub fn levels_plugin(app: &mut App) {
     app
         // When entering the state, spawn everything needed for this screen
         .add_systems(OnEnter(GameState::Levels), level_setup)
-        // While in this state, run the `countdown` system
+        // While in this state, handle input, moving elements, ball contacts with elements and the resulting events
         .add_systems(
-            First,
-            handle_event_block_destroyed.run_if(in_state(GameState::Levels)),
+            FixedUpdate,
+            (
+                handle_back_event.in_set(GameplaySet::Input),
+                (move_elements_on_paths, rotate_spinning_elements)
+                    .in_set(GameplaySet::ElementMovement),
+            )
+                .run_if(in_state(GameState::Levels)),
         )
         .add_systems(
-            Update,
+            FixedPostUpdate,
             (
-                handle_right_mouse_press_event.run_if(in_state(GameState::Levels)),
-                handle_collision_player_ball_with_destructible_element
-                    .run_if(in_state(GameState::Levels)),
-            ),
+                (
+                    transfer_spinner_velocity,
+                    kick_balls_off_bumpers,
+                    drain_balls_hitting_absorbers,
+                )
+                    .chain()
+                    .in_set(GameplaySet::BallPhysics)
+                    .before(handle_collision_player_ball),
+                (
+                    handle_collision_player_ball_with_switch,
+                    handle_collision_player_ball_with_destructible_element,
+                    handle_explosions,
+                )
+                    .chain()
+                    .in_set(GameplaySet::CollisionReaction),
+                teleport_balls_through_portals.in_set(GameplaySet::CollisionReaction),
+                (
+                    // gates turning destructible must count before the level is checked for completion
+                    (handle_switch_toggled_event, handle_event_block_destroyed).chain(),
+                    handle_life_lost_event,
+                )
+                    .in_set(GameplaySet::Events),
+            )
+                .run_if(in_state(GameState::Levels)),
         )
         // When exiting the state, despawn everything that was spawned for this screen
-        .add_systems(OnExit(GameState::Levels), despawn_screen::<OnLevelsScreen>)
+        .add_systems(
+            OnExit(GameState::Levels),
+            (
+                despawn_screen::<OnLevelsScreen>,
+                reset_playfield,
+                reset_physics_rules,
+            ),
+        )
+        .add_systems(
+            Update,
+            apply_element_tints.run_if(in_state(GameState::Levels)),
+        )
         // Events
         .add_event::<BlockDestroyedEvent>()
+        .add_event::<ExplosionEvent>()
+        .add_event::<SwitchToggledEvent>()
         // Resources
         .insert_resource(LastSelectedLevel {
             selected_level: SelectedLevel::Level1,
-        });
+        })
+        .insert_resource(Lives {
+            remaining: LIVES_PER_LEVEL,
+        })
+        .init_resource::<LevelRules>();
 }
 
+// number of times the last ball may be lost before the level is aborted
+const LIVES_PER_LEVEL: u32 = 3;
+
 /*
  * ================================================================================================================
  * START - Plugin Systems
  * ================================================================================================================
  */
 
-fn level_setup(
+pub fn level_setup(
     // Globals
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut last_selected_level: ResMut<LastSelectedLevel>,
+    mut lives: ResMut<Lives>,
+    mut level_rules: ResMut<LevelRules>,
+    mut playfield: ResMut<Playfield>,
+    mut gravity: ResMut<Gravity>,
+    mut default_restitution: ResMut<DefaultRestitution>,
+    mut default_friction: ResMut<DefaultFriction>,
     // Events
     mut level_selected_evr: EventReader<LevelSelectedEvent>,
 ) {
     debug!("Setting up Level");
 
-    for event in level_selected_evr.read() {
-        last_selected_level.selected_level = event.selected_level;
+    lives.remaining = LIVES_PER_LEVEL;
+
+    // only one level can be set up; should there be more than one selection, the first one counts
+    let selected_level = level_selected_evr
+        .read()
+        .next()
+        .map(|event| event.selected_level);
+    level_selected_evr.clear();
+
+    if let Some(selected_level) = selected_level {
+        last_selected_level.selected_level = selected_level;
 
-        match event.selected_level {
+        match selected_level {
             SelectedLevel::Level1 => {
+                *level_rules = level_1::level_rules();
                 level_1::spawn_level(&mut commands, &asset_server);
             }
 
             SelectedLevel::Level2 => {
+                *level_rules = level_2::level_rules();
                 level_2::spawn_level(&mut commands, &asset_server);
             }
 
             SelectedLevel::Level3 => {
+                *level_rules = level_3::level_rules();
                 level_3::spawn_level(&mut commands, &asset_server);
             }
 
             SelectedLevel::Level4 => {
+                *level_rules = level_4::level_rules();
                 level_4::spawn_level(&mut commands, &asset_server);
             }
 
             SelectedLevel::Level5 => {
+                *level_rules = level_5::level_rules();
                 level_5::spawn_level(&mut commands, &asset_server);
             }
 
             _ => todo!(),
         }
+
+        playfield.set_if_neq(level_rules.playfield.clone());
+        apply_physics_rules(
+            &level_rules.physics,
+            &mut gravity,
+            &mut default_restitution,
+            &mut default_friction,
+        );
     }
 }
 
+/*
+ * The selection screen always uses the default physics
+ */
+fn reset_physics_rules(
+    // Globals
+    mut gravity: ResMut<Gravity>,
+    mut default_restitution: ResMut<DefaultRestitution>,
+    mut default_friction: ResMut<DefaultFriction>,
+) {
+    apply_physics_rules(
+        &PhysicsRules::default(),
+        &mut gravity,
+        &mut default_restitution,
+        &mut default_friction,
+    );
+}
+
+/*
+ * The selection screen always uses the default arena
+ */
+fn reset_playfield(
+    // Globals
+    mut playfield: ResMut<Playfield>,
+) {
+    playfield.set_if_neq(Playfield::default());
+}
+
 fn handle_collision_player_ball_with_destructible_element(
-    // Singles
-    player_ball: Single<(Entity, &PlayerBall)>,
     // Globals
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_rules: Res<LevelRules>,
     // Collisions
     collisions: Collisions,
     // Events
     mut block_destroyed_evw: EventWriter<BlockDestroyedEvent>,
+    mut explosion_evw: EventWriter<ExplosionEvent>,
+    mut switch_toggled_evw: EventWriter<SwitchToggledEvent>,
     // Queries
-    destructible_elements: Query<&DestructibleElement>,
+    player_balls: Query<(Entity, &PlayerBall)>,
+    mut destructible_elements: Query<(
+        Entity,
+        &mut DestructibleElement,
+        &Transform,
+        &mut Sprite,
+        Option<&ExplosiveElement>,
+        Option<&Switch>,
+    )>,
 ) {
-    let (player_ball_entity, player_ball) = player_ball.into_inner();
+    // forget balls that no longer touch this element, so their next contact counts as a new hit
+    for (element_entity, mut element, _element_transform, _element_sprite, _explosive, _switch) in
+        destructible_elements.iter_mut()
+    {
+        if element.touching_balls.is_empty() {
+            continue;
+        }
+
+        element.touching_balls.retain(|touching_ball| {
+            collisions.iter().any(|contact_pair| {
+                (contact_pair.collider1.eq(touching_ball)
+                    && contact_pair.collider2.eq(&element_entity))
+                    || (contact_pair.collider2.eq(touching_ball)
+                        && contact_pair.collider1.eq(&element_entity))
+            })
+        });
+    }
 
+    // several balls may hit the same block within one frame; it must only be destroyed once
+    let mut destroyed_elements: Vec<Entity> = Vec::new();
+
+    for (player_ball_entity, player_ball) in player_balls.iter() {
+        handle_collisions_of_player_ball(
+            player_ball_entity,
+            player_ball,
+            &mut destroyed_elements,
+            &mut commands,
+            &asset_server,
+            &level_rules,
+            &collisions,
+            &mut block_destroyed_evw,
+            &mut explosion_evw,
+            &mut switch_toggled_evw,
+            &mut destructible_elements,
+        );
+    }
+}
+
+fn handle_collisions_of_player_ball(
+    // Parameters
+    player_ball_entity: Entity,
+    player_ball: &PlayerBall,
+    destroyed_elements: &mut Vec<Entity>,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    level_rules: &Res<LevelRules>,
+    // Collisions
+    collisions: &Collisions,
+    // Events
+    block_destroyed_evw: &mut EventWriter<BlockDestroyedEvent>,
+    explosion_evw: &mut EventWriter<ExplosionEvent>,
+    switch_toggled_evw: &mut EventWriter<SwitchToggledEvent>,
+    // Queries
+    destructible_elements: &mut Query<(
+        Entity,
+        &mut DestructibleElement,
+        &Transform,
+        &mut Sprite,
+        Option<&ExplosiveElement>,
+        Option<&Switch>,
+    )>,
+) {
     for contact_pair in collisions.iter() {
         // if one of the colliders is the player ball and one of them is the bottom collider
         if (contact_pair.collider1.eq(&player_ball_entity)
@@ -113,7 +298,6 @@ fn handle_collision_player_ball_with_destructible_element(
             && (destructible_elements.contains(contact_pair.collider1)
                 || destructible_elements.contains(contact_pair.collider2))
         {
-            trace!("Ball hit a block");
             let mut element_entity;
             if contact_pair.collider1.eq(&player_ball_entity) {
                 element_entity = contact_pair.collider2;
@@ -121,17 +305,245 @@ fn handle_collision_player_ball_with_destructible_element(
                 element_entity = contact_pair.collider1;
             }
 
-            if let Some(element) = destructible_elements.get(element_entity).ok() {
-                if ball_destroys_element(&player_ball.power_level, &element.element_durability) {
+            if destroyed_elements.contains(&element_entity) {
+                continue;
+            }
+
+            if let Some((
+                _element_entity,
+                mut element,
+                element_transform,
+                mut element_sprite,
+                explosive,
+                switch,
+            )) = destructible_elements.get_mut(element_entity).ok()
+            {
+                // still the same contact as in a previous frame
+                if element.touching_balls.contains(&player_ball_entity) {
+                    continue;
+                }
+                element.touching_balls.push(player_ball_entity);
+                trace!("Ball hit a block");
+
+                let damage = calculate_damage(
+                    &level_rules.damage_model,
+                    &player_ball.power_level,
+                    &element,
+                );
+                element.hit_points = element.hit_points.saturating_sub(damage);
+
+                if element.hit_points == 0 {
                     commands.entity(element_entity).despawn();
-                    block_destroyed_evw.write(BlockDestroyedEvent);
+                    destroyed_elements.push(element_entity);
+                    block_destroyed_evw.write(BlockDestroyedEvent {
+                        position: element_transform.translation.truncate(),
+                    });
                     debug!("Block destroyed, event fired");
+
+                    if let Some(explosive) = explosive {
+                        explosion_evw.write(ExplosionEvent {
+                            position: element_transform.translation.truncate(),
+                            blast_radius: explosive.blast_radius,
+                            power_level: player_ball.power_level,
+                        });
+                    }
+
+                    if let Some(switch) = switch {
+                        debug!("Switch {} destroyed", switch.link_id);
+                        switch_toggled_evw.write(SwitchToggledEvent {
+                            link_id: switch.link_id,
+                        });
+                    }
+                } else if damage > 0 {
+                    update_damaged_element_sprite(&element, &mut element_sprite, asset_server);
+                    debug!("Block damaged, {} hit points left", element.hit_points);
+                }
+            }
+        }
+    }
+}
+
+/*
+ * Indestructible switches toggle with every new contact
+ * Destructible switches only toggle when they are destroyed, see handle_collisions_of_player_ball
+ */
+fn handle_collision_player_ball_with_switch(
+    // Collisions
+    collisions: Collisions,
+    // Events
+    mut switch_toggled_evw: EventWriter<SwitchToggledEvent>,
+    // Queries
+    player_balls: Query<(), With<PlayerBall>>,
+    mut switches: Query<(Entity, &mut Switch), Without<DestructibleElement>>,
+) {
+    for (switch_entity, mut switch) in switches.iter_mut() {
+        // forget balls that no longer touch the switch, so their next contact counts again
+        switch.touching_balls.retain(|touching_ball| {
+            collisions.iter().any(|contact_pair| {
+                (contact_pair.collider1.eq(touching_ball)
+                    && contact_pair.collider2.eq(&switch_entity))
+                    || (contact_pair.collider2.eq(touching_ball)
+                        && contact_pair.collider1.eq(&switch_entity))
+            })
+        });
+
+        for contact_pair in collisions.iter() {
+            let ball_entity;
+            if contact_pair.collider1.eq(&switch_entity) {
+                ball_entity = contact_pair.collider2;
+            } else if contact_pair.collider2.eq(&switch_entity) {
+                ball_entity = contact_pair.collider1;
+            } else {
+                continue;
+            }
+
+            if !player_balls.contains(ball_entity) {
+                continue;
+            }
+
+            // still the same contact as in a previous frame
+            if switch.touching_balls.contains(&ball_entity) {
+                continue;
+            }
+            switch.touching_balls.push(ball_entity);
+
+            debug!("Switch {} toggled", switch.link_id);
+            switch_toggled_evw.write(SwitchToggledEvent {
+                link_id: switch.link_id,
+            });
+        }
+    }
+}
+
+/*
+ * Explosions damage every destructible element in their blast like a ball of the power level that set them off
+ * Explosives destroyed by a blast explode as well; the whole chain is resolved within the frame,
+ * nearest elements first, so the same hit always unfolds the same way
+ */
+fn handle_explosions(
+    // Globals
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_rules: Res<LevelRules>,
+    // Events
+    mut explosion_evr: EventReader<ExplosionEvent>,
+    mut block_destroyed_evw: EventWriter<BlockDestroyedEvent>,
+    mut switch_toggled_evw: EventWriter<SwitchToggledEvent>,
+    // Queries
+    mut destructible_elements: Query<(
+        Entity,
+        &mut DestructibleElement,
+        &Transform,
+        &mut Sprite,
+        Option<&ExplosiveElement>,
+        Option<&Switch>,
+    )>,
+) {
+    let mut pending_explosions: VecDeque<ExplosionEvent> = explosion_evr.read().cloned().collect();
+    let mut destroyed_elements: Vec<Entity> = Vec::new();
+
+    while let Some(explosion) = pending_explosions.pop_front() {
+        debug!(
+            "Explosion with a blast radius of {}",
+            explosion.blast_radius
+        );
+
+        let mut elements_in_blast: Vec<(Entity, f32)> = destructible_elements
+            .iter()
+            .filter(|(element_entity, ..)| !destroyed_elements.contains(element_entity))
+            .map(|(element_entity, _element, element_transform, ..)| {
+                (
+                    element_entity,
+                    element_transform
+                        .translation
+                        .truncate()
+                        .distance(explosion.position),
+                )
+            })
+            .filter(|(_element_entity, distance)| *distance <= explosion.blast_radius)
+            .collect();
+
+        elements_in_blast.sort_by(|(entity_a, distance_a), (entity_b, distance_b)| {
+            distance_a
+                .total_cmp(distance_b)
+                .then(entity_a.cmp(entity_b))
+        });
+
+        for (element_entity, _distance) in elements_in_blast {
+            let Ok((
+                _element_entity,
+                mut element,
+                element_transform,
+                mut element_sprite,
+                explosive,
+                switch,
+            )) = destructible_elements.get_mut(element_entity)
+            else {
+                continue;
+            };
+
+            let damage =
+                calculate_damage(&level_rules.damage_model, &explosion.power_level, &element);
+            element.hit_points = element.hit_points.saturating_sub(damage);
+
+            if element.hit_points == 0 {
+                commands.entity(element_entity).despawn();
+                destroyed_elements.push(element_entity);
+                block_destroyed_evw.write(BlockDestroyedEvent {
+                    position: element_transform.translation.truncate(),
+                });
+                debug!("Block destroyed by an explosion, event fired");
+
+                if let Some(explosive) = explosive {
+                    pending_explosions.push_back(ExplosionEvent {
+                        position: element_transform.translation.truncate(),
+                        blast_radius: explosive.blast_radius,
+                        power_level: explosion.power_level,
+                    });
+                }
+
+                if let Some(switch) = switch {
+                    debug!("Switch {} destroyed by an explosion", switch.link_id);
+                    switch_toggled_evw.write(SwitchToggledEvent {
+                        link_id: switch.link_id,
+                    });
                 }
+            } else if damage > 0 {
+                update_damaged_element_sprite(&element, &mut element_sprite, &asset_server);
+                debug!(
+                    "Block damaged by an explosion, {} hit points left",
+                    element.hit_points
+                );
             }
         }
     }
 }
 
+/*
+ * A ball that is strong enough destroys the element in one hit
+ * Weaker balls chip away hit points, unless the level uses the threshold damage model
+ */
+fn calculate_damage(
+    damage_model: &DamageModel,
+    player_ball_power_level: &PowerLevel,
+    element: &DestructibleElement,
+) -> u32 {
+    if ball_destroys_element(player_ball_power_level, &element.element_durability) {
+        return element.hit_points;
+    }
+
+    match damage_model {
+        DamageModel::Threshold => 0,
+        DamageModel::HitPoints => match player_ball_power_level {
+            PowerLevel::Lowest => 1,
+            PowerLevel::Low => 2,
+            PowerLevel::Medium => 3,
+            PowerLevel::High => 4,
+            PowerLevel::Highest => 5,
+        },
+    }
+}
+
 fn ball_destroys_element(
     player_ball_power_level: &PowerLevel,
     element_durability: &ElementDurability,
@@ -155,44 +567,65 @@ fn ball_destroys_element(
     }
 }
 
-fn handle_right_mouse_press_event(
+fn handle_back_event(
     // Globals
     mut game_state: ResMut<NextState<GameState>>,
     // Events
-    mut right_mouse_press_evr: EventReader<RightMousePressEvent>,
+    mut back_evr: EventReader<BackEvent>,
     mut ball_destroyed_evw: EventWriter<BallDestroyedEvent>,
+    // Queries
+    player_balls: Query<Entity, With<PlayerBall>>,
 ) {
-    for _event in right_mouse_press_evr.read() {
-        debug!("Right Mouse press event received; returning to level selection");
-        ball_destroyed_evw.write(BallDestroyedEvent);
+    for _event in back_evr.read() {
+        debug!("Back event received; returning to level selection");
+        destroy_all_balls(&player_balls, &mut ball_destroyed_evw);
         game_state.set(GameState::Selection);
         break;
     }
 }
 
+fn handle_switch_toggled_event(
+    // Globals
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    // Events
+    mut switch_toggled_evr: EventReader<SwitchToggledEvent>,
+    // Queries
+    mut gates: Query<(Entity, &mut Gate, &mut Transform, &mut Visibility)>,
+) {
+    for event in switch_toggled_evr.read() {
+        toggle_gates(event.link_id, &mut commands, &asset_server, &mut gates);
+    }
+}
+
 fn handle_event_block_destroyed(
     // Singles
     mut player: Single<&mut Player>,
     // Globals
     last_selected_level: Res<LastSelectedLevel>,
+    attract_mode: Res<AttractMode>,
     mut game_state: ResMut<NextState<GameState>>,
     mut ball_destroyed_evw: EventWriter<BallDestroyedEvent>,
     // Events
     mut block_destroyed_evr: EventReader<BlockDestroyedEvent>,
     // Queries
     destructible_elements: Query<&DestructibleElement>,
+    player_balls: Query<Entity, With<PlayerBall>>,
 ) {
     let mut player = player.into_inner();
     for event in block_destroyed_evr.read() {
         debug!("Block destroyed event read");
         if destructible_elements.is_empty() {
             debug!("all elements destroyed");
-            player.highest_selectable_level = unlock_next_level(
-                player.highest_selectable_level,
-                last_selected_level.selected_level,
-            );
-            // destroy ball and return to level selection
-            ball_destroyed_evw.write(BallDestroyedEvent);
+            // a demo game played by the autopilot does not count as progress
+            if !attract_mode.active {
+                player.highest_selectable_level = unlock_next_level(
+                    player.highest_selectable_level,
+                    last_selected_level.selected_level,
+                );
+            }
+            // destroy balls and return to level selection
+            destroy_all_balls(&player_balls, &mut ball_destroyed_evw);
             game_state.set(GameState::Selection);
             break;
         } else {
@@ -201,6 +634,45 @@ fn handle_event_block_destroyed(
     }
 }
 
+/*
+ * Each time the last ball in play is lost, a life is lost; without lives left, the player returns to level selection
+ */
+fn handle_life_lost_event(
+    // Globals
+    mut lives: ResMut<Lives>,
+    mut game_state: ResMut<NextState<GameState>>,
+    // Events
+    mut life_lost_evr: EventReader<LifeLostEvent>,
+) {
+    for _event in life_lost_evr.read() {
+        lives.remaining = lives.remaining.saturating_sub(1);
+        debug!("Life lost, {} remaining", lives.remaining);
+
+        if lives.remaining == 0 {
+            debug!("No lives left; returning to level selection");
+            game_state.set(GameState::Selection);
+            break;
+        }
+    }
+}
+
+/*
+ * Sends a (not lost) destroyed event for every ball in play; used when leaving a level
+ */
+fn destroy_all_balls(
+    // Queries
+    player_balls: &Query<Entity, With<PlayerBall>>,
+    // Events
+    ball_destroyed_evw: &mut EventWriter<BallDestroyedEvent>,
+) {
+    for player_ball in player_balls.iter() {
+        ball_destroyed_evw.write(BallDestroyedEvent {
+            ball: player_ball,
+            ball_lost: false,
+        });
+    }
+}
+
 /*
  * Return the new highest selectable level
  * Only unlocks the next level if the player has actually cleared the highest selectable level
@@ -269,6 +741,30 @@ pub enum SelectedLevel {
  * ================================================================================================================
  */
 
+/*
+ * ================================================================================================================
+ * START - Enumerations
+ * ================================================================================================================
+ */
+
+/*
+ * Defines how a ball hit damages a destructible element
+ */
+#[derive(Default, Eq, PartialEq)]
+pub enum DamageModel {
+    // only a ball with enough power destroys the element, weaker hits do nothing
+    Threshold,
+    // weaker hits chip away hit points, a ball with enough power destroys the element in one hit
+    #[default]
+    HitPoints,
+}
+
+/*
+ * ================================================================================================================
+ * END - Enumerations
+ * ================================================================================================================
+ */
+
 /*
  * ================================================================================================================
  * START - Events
@@ -276,7 +772,28 @@ pub enum SelectedLevel {
  */
 
 #[derive(Event)]
-struct BlockDestroyedEvent;
+pub struct BlockDestroyedEvent {
+    pub position: Vec2,
+}
+
+/*
+ * Sent when a switch is hit (or destroyed); toggles all gates with the same link id
+ */
+#[derive(Event)]
+struct SwitchToggledEvent {
+    link_id: u32,
+}
+
+/*
+ * Sent when an explosive element is destroyed
+ */
+#[derive(Event, Clone)]
+struct ExplosionEvent {
+    position: Vec2,
+    blast_radius: f32,
+    // power level of the ball that started the chain; decides which elements the blast destroys
+    power_level: PowerLevel,
+}
 
 /*
  * ================================================================================================================
@@ -284,7 +801,39 @@ struct BlockDestroyedEvent;
  * ================================================================================================================
  */
 
+/*
+ * ================================================================================================================
+ * START - Resources
+ * ================================================================================================================
+ */
+
 #[derive(Resource)]
-struct LastSelectedLevel {
+pub struct LastSelectedLevel {
     selected_level: SelectedLevel,
 }
+
+/*
+ * Rules that differ between levels; every level defines its own
+ */
+#[derive(Resource, Default)]
+pub struct LevelRules {
+    pub damage_model: DamageModel,
+    pub playfield: Playfield,
+    pub physics: PhysicsRules,
+    // a second paddle mirroring the player's, if any
+    pub mirrored_paddle: Option<PaddleMirror>,
+}
+
+/*
+ * Lives left in the current level; shared by all balls
+ */
+#[derive(Resource)]
+pub struct Lives {
+    pub remaining: u32,
+}
+
+/*
+ * ================================================================================================================
+ * END - Resources
+ * ================================================================================================================
+ */
@@ -1,6 +1,17 @@
 use bevy::prelude::*;
 
 use crate::level_elements::*;
+use crate::levels::{DamageModel, LevelRules};
+
+/*
+ * The columns teach the power levels, so blocks only break once the ball is strong enough
+ */
+pub fn level_rules() -> LevelRules {
+    LevelRules {
+        damage_model: DamageModel::Threshold,
+        ..Default::default()
+    }
+}
 
 pub fn spawn_level(
     // Globals
@@ -23,4 +34,47 @@ pub fn spawn_level(
             &asset_server,
         );
     }
+
+    // bumpers below the columns help the ball reach the next power level
+    for x_coordinate in [-125.0, 175.0] {
+        spawn_bumper(
+            ElementShape::Pentagon,
+            Vec2::new(x_coordinate, -220.0),
+            BumperKick::default(),
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    // a switch between the bumpers opens the outer lanes and slides the lid off the top of the columns
+    spawn_switch(
+        ElementDurability::Indestructible,
+        ElementShape::Square,
+        Vec2::new(25.0, -220.0),
+        1,
+        &mut commands,
+        &asset_server,
+    );
+
+    for x_coordinate in [-475.0, 525.0] {
+        spawn_gate(
+            ElementShape::Rectangle,
+            Vec2::new(x_coordinate, 0.0),
+            1,
+            GateAction::Disappear,
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    spawn_gate(
+        ElementShape::Rectangle,
+        Vec2::new(25.0, 250.0),
+        1,
+        GateAction::Slide {
+            offset: Vec2::new(450.0, 0.0),
+        },
+        &mut commands,
+        &asset_server,
+    );
 }
@@ -1,6 +1,14 @@
 use bevy::prelude::*;
 
 use crate::level_elements::*;
+use crate::levels::{DamageModel, LevelRules};
+
+pub fn level_rules() -> LevelRules {
+    LevelRules {
+        damage_model: DamageModel::HitPoints,
+        ..Default::default()
+    }
+}
 
 pub fn spawn_level(
     // Globals
@@ -48,4 +56,37 @@ pub fn spawn_level(
             &asset_server,
         );
     }
+
+    // a switch below the center column turns the two blocks above the crosses destructible
+    spawn_switch(
+        ElementDurability::Indestructible,
+        ElementShape::Square,
+        Vec2::new(0.0, -220.0),
+        1,
+        &mut commands,
+        &asset_server,
+    );
+
+    for x_coordinate in [-200.0, 200.0] {
+        spawn_gate(
+            ElementShape::Rectangle,
+            Vec2::new(x_coordinate, 250.0),
+            1,
+            GateAction::BecomeDestructible {
+                element_durability: ElementDurability::Medium,
+            },
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    // portals from the lower corners up to the opposite upper corners
+    spawn_mirrored_portal_pairs(
+        Vec2::new(-520.0, -220.0),
+        Vec2::new(520.0, 250.0),
+        PortalDirection::Keep,
+        Color::srgb(0.4, 0.8, 1.0),
+        &mut commands,
+        &asset_server,
+    );
 }
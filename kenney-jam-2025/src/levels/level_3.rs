@@ -1,6 +1,28 @@
 use bevy::prelude::*;
 
 use crate::level_elements::*;
+use crate::levels::{DamageModel, LevelRules};
+use crate::mirrored_paddle::PaddleMirror;
+use crate::{PhysicsRules, Playfield};
+
+/*
+ * The diamond is narrow, so is the arena; without gravity the ball keeps its line,
+ * so a second paddle at the top guards against the ball flying out there
+ */
+pub fn level_rules() -> LevelRules {
+    LevelRules {
+        damage_model: DamageModel::HitPoints,
+        playfield: Playfield {
+            half_width: 455.0,
+            ..Default::default()
+        },
+        physics: PhysicsRules {
+            gravity: Vec2::ZERO,
+            ..Default::default()
+        },
+        mirrored_paddle: Some(PaddleMirror::Horizontal),
+    }
+}
 
 pub fn spawn_level(
     // Globals
@@ -56,4 +78,29 @@ pub fn spawn_level(
             );
         }
     }
+
+    // explosives on both tips of the diamond, each blast reaching into its side
+    let center_row_y = bottom_row_y + (rows_total / 2) as f32 * block_delta * 0.5;
+    let explosive_x = 0.5 * (rows_total / 2) as f32 * block_delta + block_delta;
+
+    for x in [-explosive_x, explosive_x] {
+        spawn_explosive_element(
+            ElementDurability::Lowest,
+            ElementShape::Pentagon,
+            Vec2::new(x, center_row_y),
+            120.0,
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    // portals at the lower sides send the ball straight up into the diamond from below
+    spawn_mirrored_portal_pairs(
+        Vec2::new(-400.0, -150.0),
+        Vec2::new(-120.0, -230.0),
+        PortalDirection::Fixed { direction: Vec2::Y },
+        Color::srgb(1.0, 0.6, 0.9),
+        &mut commands,
+        &asset_server,
+    );
 }
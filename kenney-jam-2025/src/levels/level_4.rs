@@ -1,6 +1,20 @@
 use bevy::prelude::*;
 
 use crate::level_elements::*;
+use crate::levels::{DamageModel, LevelRules};
+use crate::mirrored_paddle::PaddleMirror;
+
+/*
+ * Everything here moves, so a second paddle at the bottom, mirrored across the center and moving inversely,
+ * helps to catch the ball
+ */
+pub fn level_rules() -> LevelRules {
+    LevelRules {
+        damage_model: DamageModel::HitPoints,
+        mirrored_paddle: Some(PaddleMirror::Vertical),
+        ..Default::default()
+    }
+}
 
 pub fn spawn_level(
     // Globals
@@ -9,5 +23,82 @@ pub fn spawn_level(
 ) {
     debug!("spawning level 4");
 
-    // TODO spawn level 2 layout
+    // top row: squares sliding across the playfield, starting over on the left
+    let square_count = 6;
+    for index in 0..square_count {
+        spawn_moving_element(
+            ElementDurability::Low,
+            ElementShape::Square,
+            Vec2::new(-480.0, 250.0),
+            ElementPath::Linear {
+                offset: Vec2::new(960.0, 0.0),
+            },
+            PathMotion {
+                speed: 80.0,
+                phase: index as f32 / square_count as f32,
+                ..Default::default()
+            },
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    // two rectangles on each side, moving towards the center and back in sync
+    for (y_position, durability) in [
+        (150.0, ElementDurability::Medium),
+        (50.0, ElementDurability::Lowest),
+    ] {
+        spawn_mirrored_moving_elements(
+            durability,
+            ElementShape::Rectangle,
+            Vec2::new(-450.0, y_position),
+            ElementPath::PingPong {
+                offset: Vec2::new(300.0, 0.0),
+            },
+            PathMotion {
+                speed: 120.0,
+                ..Default::default()
+            },
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    // ring of pentagons orbiting a diamond at the center
+    spawn_element(
+        ElementDurability::Highest,
+        ElementShape::Diamond,
+        Vec2::new(0.0, 0.0),
+        &mut commands,
+        &asset_server,
+    );
+
+    let pentagon_count = 4;
+    for index in 0..pentagon_count {
+        spawn_moving_element(
+            ElementDurability::High,
+            ElementShape::Pentagon,
+            Vec2::new(0.0, 0.0),
+            ElementPath::Circular { radius: 90.0 },
+            PathMotion {
+                speed: 90.0,
+                phase: index as f32 / pentagon_count as f32,
+                ..Default::default()
+            },
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    // portals at the sides lead across the playfield, turning the ball upwards
+    spawn_mirrored_portal_pairs(
+        Vec2::new(-560.0, -50.0),
+        Vec2::new(540.0, -180.0),
+        PortalDirection::Rotate {
+            angle: -core::f32::consts::FRAC_PI_2,
+        },
+        Color::srgb(1.0, 0.8, 0.3),
+        &mut commands,
+        &asset_server,
+    );
 }
@@ -1,6 +1,20 @@
 use bevy::prelude::*;
 
 use crate::level_elements::*;
+use crate::levels::{DamageModel, LevelRules};
+use crate::mirrored_paddle::PaddleMirror;
+
+/*
+ * The flippers and spinners throw the ball upwards hard, so the top is guarded by a second paddle
+ * mirrored through the center, moving inversely; a ball getting past it is lost
+ */
+pub fn level_rules() -> LevelRules {
+    LevelRules {
+        damage_model: DamageModel::HitPoints,
+        mirrored_paddle: Some(PaddleMirror::Central),
+        ..Default::default()
+    }
+}
 
 pub fn spawn_level(
     // Globals
@@ -9,5 +23,49 @@ pub fn spawn_level(
 ) {
     debug!("spawning level 5");
 
-    // TODO spawn level 5 layout
+    // a block column in the center, with one on each side
+    for x_coordinate in [-450.0, 0.0, 450.0] {
+        spawn_square_block_column(x_coordinate, 0.0, 60.0, &mut commands, &asset_server);
+    }
+
+    // absorbers above the center column, so the ball loses power when it gets there too easily
+    for x_coordinate in [-100.0, 100.0] {
+        spawn_absorber_element(
+            ElementDurability::Medium,
+            ElementShape::Rectangle,
+            Vec2::new(x_coordinate, 260.0),
+            1,
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    // two spinners between the columns, turning in opposite directions
+    for (x_coordinate, angular_speed) in [(-225.0, 2.0), (225.0, -2.0)] {
+        spawn_spinning_element(
+            ElementDurability::Indestructible,
+            ElementShape::Rectangle,
+            Vec2::new(x_coordinate, 100.0),
+            Vec2::ZERO,
+            SpinMotion::Continuous { angular_speed },
+            &mut commands,
+            &asset_server,
+        );
+    }
+
+    // two flippers pivoting at their outer ends, swinging towards the center and back
+    for (x_coordinate, pivot_offset, swing_angle) in [(-300.0, -32.0, 0.6), (300.0, 32.0, -0.6)] {
+        spawn_spinning_element(
+            ElementDurability::Indestructible,
+            ElementShape::Rectangle,
+            Vec2::new(x_coordinate, -150.0),
+            Vec2::new(pivot_offset, 0.0),
+            SpinMotion::Flipper {
+                swing_angle,
+                swing_period: 2.0,
+            },
+            &mut commands,
+            &asset_server,
+        );
+    }
 }
@@ -2,6 +2,11 @@ use avian2d::prelude::*;
 use bevy::{log::*, prelude::*};
 use std::ops::*;
 
+mod actions;
+use crate::actions::{
+    LaunchPressEvent, LaunchReleaseEvent, MovePaddleByEvent, MovePaddleToEvent, PauseEvent,
+};
+
 mod levels;
 use crate::levels::SelectedLevel;
 
@@ -9,6 +14,22 @@ mod selection;
 
 mod level_elements;
 
+mod power_ups;
+
+mod shield;
+
+mod mirrored_paddle;
+use crate::mirrored_paddle::TopCollider;
+
+mod coop;
+
+mod autopilot;
+
+mod attract_mode;
+
+#[cfg(test)]
+mod tunneling_check;
+
 fn main() -> AppExit {
     App::new()
         // ========= PLUGINS
@@ -27,37 +48,81 @@ fn main() -> AppExit {
         // Debug physics
         .add_plugins(PhysicsDebugPlugin::default())
         // Game plugins
-        .add_plugins((selection::selection_plugin, levels::levels_plugin))
+        .add_plugins((
+            actions::actions_plugin,
+            selection::selection_plugin,
+            levels::levels_plugin,
+            power_ups::power_ups_plugin,
+            shield::shield_plugin,
+            mirrored_paddle::mirrored_paddle_plugin,
+            coop::coop_plugin,
+            autopilot::autopilot_plugin,
+            attract_mode::attract_mode_plugin,
+        ))
+        // ========= SYSTEM SETS
+        // Gameplay runs in the fixed schedules around avian's physics step (FixedPostUpdate),
+        // so identical input sequences give identical results regardless of frame rate
+        .configure_sets(
+            FixedUpdate,
+            (GameplaySet::Input, GameplaySet::ElementMovement).chain(),
+        )
+        .configure_sets(
+            FixedPostUpdate,
+            (
+                GameplaySet::BallPhysics,
+                GameplaySet::CollisionReaction,
+                GameplaySet::Events,
+            )
+                .chain()
+                .after(PhysicsSet::Sync),
+        )
         // ========= SYSTEMS
         .add_systems(Startup, setup)
-        .add_systems(FixedUpdate, handle_raw_input)
-        .add_systems(PreUpdate, handle_collision_player_ball)
         .add_systems(
-            Update,
+            FixedUpdate,
             (
-                handle_left_mouse_press_events,
-                handle_left_mouse_release_events,
-                handle_mouse_move_events,
-                handle_ball_destroyed_event,
+                handle_move_paddle_to_events,
+                handle_move_paddle_by_events,
+                drive_paddle_towards_target,
+                handle_launch_press_events,
+                update_launch_aim,
+                handle_launch_release_events,
+            )
+                .chain()
+                .in_set(GameplaySet::Input),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            (
+                (transfer_paddle_momentum, handle_collision_player_ball)
+                    .chain()
+                    .in_set(GameplaySet::BallPhysics),
+                (
+                    player_ball_physics_sanity_check,
+                    player_ball_loop_check,
+                    handle_collision_player_ball_and_bottom_collider,
+                )
+                    .in_set(GameplaySet::CollisionReaction),
+                handle_ball_destroyed_event.in_set(GameplaySet::Events),
             ),
         )
         .add_systems(
-            PostUpdate,
+            Update,
             (
-                player_ball_physics_sanity_check,
-                handle_collision_player_ball_and_bottom_collider,
+                update_playfield_borders.run_if(resource_changed::<Playfield>),
+                // while paused, the fixed schedules do not run
+                handle_pause_events,
             ),
         )
         .add_systems(Last, add_colliders)
         // ========= EVENTS
-        .add_event::<MouseMoveEvent>()
         .add_event::<BallDestroyedEvent>()
-        .add_event::<LeftMousePressEvent>()
-        .add_event::<LeftMouseReleaseEvent>()
+        .add_event::<LifeLostEvent>()
         .add_event::<ElementDestroyedEvent>()
-        .add_event::<RightMousePressEvent>()
         // ========= RESOURCE
-        .insert_resource(Gravity(Vec2::new(0.0, -1.0)))
+        .init_resource::<CursorWorldPosition>()
+        .init_resource::<GameRng>()
+        .init_resource::<Playfield>()
         // ========= GAME STATE
         // Declare the game state, whose starting value is determined by the `Default` trait
         .init_state::<GameState>()
@@ -65,6 +130,49 @@ fn main() -> AppExit {
         .run()
 }
 
+/*
+ * ================================================================================================================
+ * START - Constants
+ * ================================================================================================================
+ */
+
+// launch impulses applied to the ball when released from the paddle
+// (a plain click launches with the default impulse, dragging further strengthens the launch up to the maximum)
+const LAUNCH_IMPULSE_DEFAULT: f32 = 130000.0;
+const LAUNCH_IMPULSE_MAX: f32 = 200000.0;
+
+// the ball sprite is drawn (and its collider built) at this scale
+const PLAYER_BALL_SCALE: f32 = 1.3;
+
+// the fastest a ball can ever move, in pixels per second
+const PLAYER_BALL_MAX_SPEED: f32 = 1000.0;
+
+// height above the paddle at which a ball is held
+const BALL_HOLD_OFFSET: f32 = 40.0;
+
+// horizontal scale of the paddle sprite (and therefore its collider) without any power-ups
+const PADDLE_BASE_SCALE_X: f32 = 1.3;
+
+// the fastest the paddle follows the cursor, in pixels per second
+const PADDLE_MAX_SPEED: f32 = 4000.0;
+
+// share of the paddle's horizontal velocity handed to a ball hitting it, and the spin it adds
+const PADDLE_MOMENTUM_TRANSFER: f32 = 0.3;
+const PADDLE_SPIN_FACTOR: f32 = 0.01; // radians per second, per pixel per second of paddle velocity
+
+// velocities at which the ball reaches the next power level
+const POWER_LEVEL_THRESHOLDS: [f32; 4] = [200.0, 400.0, 600.0, 800.0];
+
+// launch trajectory preview
+const TRAJECTORY_PREVIEW_DOT_COUNT: usize = 8;
+const TRAJECTORY_PREVIEW_TIME_STEP: f32 = 0.08; // in seconds
+
+/*
+ * ================================================================================================================
+ * END - Constants
+ * ================================================================================================================
+ */
+
 /*
  * ================================================================================================================
  * START - Systems
@@ -79,9 +187,11 @@ fn setup(
     // Globals
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut gravity: ResMut<Gravity>,
     mut default_restitution: ResMut<DefaultRestitution>,
     mut default_friction: ResMut<DefaultFriction>,
     mut game_state: ResMut<NextState<GameState>>,
+    playfield: Res<Playfield>,
 ) {
     commands.spawn(Camera2d);
 
@@ -100,461 +210,652 @@ fn setup(
             collider_type: ColliderType::Capsule,
         },
         sprite: Sprite::from_image(asset_server.load("paddleBlu.png")),
-        transform: Transform::from_xyz(0.0, -300.0, 0.0).with_scale(Vec3::new(1.3, 1.0, 1.0)),
-        rigid_body: RigidBody::Static,
+        transform: Transform::from_xyz(0.0, playfield.paddle_y(), 0.0).with_scale(Vec3::new(
+            PADDLE_BASE_SCALE_X,
+            1.0,
+            1.0,
+        )),
+        rigid_body: RigidBody::Kinematic,
+        motion: PaddleMotion {
+            target_x: 0.0,
+            max_speed: Some(PADDLE_MAX_SPEED),
+            area: PaddleArea::Full,
+            touching_balls: Vec::new(),
+        },
     });
     //pre-load red paddle
     let _red_paddle = Sprite::from_image(asset_server.load("paddleRed.png"));
 
-    // left border
-    let mut permanent_element_transform = Transform::default();
-    permanent_element_transform.translation = Vec3::new(-615.0, 0.0, 0.0);
-    use core::f32::consts::FRAC_PI_2; // half a turn = 𝛕/2 = 180°
-    permanent_element_transform.rotate_z(FRAC_PI_2);
-    permanent_element_transform.scale.x = 3.8;
-
-    commands.spawn(PermanentElementBundle {
-        marker: PermanentElement,
-        add_collider: AddCollider {
-            collider_scale: 1.0,
-            collider_type: ColliderType::RoundedRectangle,
-        },
-        sprite: Sprite::from_image(asset_server.load("buttonSelected.png")),
-        transform: permanent_element_transform,
-        rigid_body: RigidBody::Static,
-    });
-
-    // right border
-    permanent_element_transform.translation = Vec3::new(615.0, 0.0, 0.0);
-    use core::f32::consts::PI; // half a turn = 𝛕/2 = 180°
-    permanent_element_transform.rotate_z(-PI);
-
-    commands.spawn(PermanentElementBundle {
-        marker: PermanentElement,
-        add_collider: AddCollider {
-            collider_scale: 1.0,
-            collider_type: ColliderType::RoundedRectangle,
-        },
-        sprite: Sprite::from_image(asset_server.load("buttonSelected.png")),
-        transform: permanent_element_transform,
-        rigid_body: RigidBody::Static,
-    });
-
-    // top border
-    permanent_element_transform.translation = Vec3::new(0.0, 335.0, 0.0);
-    permanent_element_transform.rotate_z(FRAC_PI_2);
-    permanent_element_transform.scale.x = 6.2;
-
-    commands.spawn(PermanentElementBundle {
-        marker: PermanentElement,
-        add_collider: AddCollider {
-            collider_scale: 1.0,
-            collider_type: ColliderType::RoundedRectangle,
-        },
-        sprite: Sprite::from_image(asset_server.load("buttonSelected.png")),
-        transform: permanent_element_transform,
-        rigid_body: RigidBody::Static,
-    });
+    for border in [
+        PlayfieldBorder::Left,
+        PlayfieldBorder::Right,
+        PlayfieldBorder::Top,
+    ] {
+        commands.spawn(PermanentElementBundle {
+            marker: PermanentElement,
+            add_collider: AddCollider {
+                collider_scale: 1.0,
+                collider_type: ColliderType::RoundedRectangle,
+            },
+            sprite: Sprite::from_image(asset_server.load("buttonSelected.png")),
+            transform: playfield_border_transform(&playfield, &border),
+            rigid_body: RigidBody::Static,
+            border,
+        });
+    }
 
     commands.spawn(BottomColliderBundle {
         marker: BottomCollider,
-        collider: Collider::rectangle(1280.0, 60.0),
-        transform: Transform::from_xyz(0.0, -385.0, 0.0),
+        collider: Collider::rectangle(
+            playfield.bottom_sensor_size().x,
+            playfield.bottom_sensor_size().y,
+        ),
+        transform: Transform::from_xyz(0.0, playfield.bottom_sensor_y(), 0.0),
         rigid_body: RigidBody::Static,
     });
 
-    default_restitution.coefficient = 1.0;
-    default_restitution.combine_rule = CoefficientCombine::Max;
-
-    default_friction.dynamic_coefficient = 0.0;
+    apply_physics_rules(
+        &PhysicsRules::default(),
+        &mut gravity,
+        &mut default_restitution,
+        &mut default_friction,
+    );
 
     trace!("Setting Game State to selection");
     game_state.set(GameState::Selection);
 }
 
 /*
- * Handle Input triggers in FixedUpdate loop
- * Note: should fire events to be handled in Update loop
- */
-fn handle_raw_input(
-    input: Res<ButtonInput<MouseButton>>,
+* Handle the action events in FixedUpdate loop
+* Pointing moves the paddle towards the pointer; while a ball is held, it aims the launch instead
+*/
+fn handle_move_paddle_to_events(
+    //Singles
+    player_paddle: Single<(&Transform, &mut PaddleMotion), With<PlayerPaddle>>,
+    // Globals
+    mut cursor_world_position: ResMut<CursorWorldPosition>,
     // Events
-    mut cursor_evr: EventReader<CursorMoved>,
-    mut mouse_move_evw: EventWriter<MouseMoveEvent>,
-    mut left_mouse_press_evw: EventWriter<LeftMousePressEvent>,
-    mut left_mouse_release_evw: EventWriter<LeftMouseReleaseEvent>,
-    mut right_mouse_press_evw: EventWriter<RightMousePressEvent>,
+    mut move_paddle_to_evr: EventReader<MovePaddleToEvent>,
+    // Queries
+    mut held_player_balls: Query<&mut Transform, (With<PlayerBallInHold>, Without<PlayerPaddle>)>,
+    flying_player_balls: Query<(), (With<PlayerBall>, Without<PlayerBallInHold>)>,
 ) {
-    // Press Left Mouse
-    if input.just_pressed(MouseButton::Left) {
-        trace!("Left pressed");
-
-        left_mouse_press_evw.write(LeftMousePressEvent);
-    }
+    let (paddle_transform, mut paddle_motion) = player_paddle.into_inner();
 
-    // Release Left Mouse
-    if input.just_released(MouseButton::Left) {
-        trace!("Left pressed");
+    for event in move_paddle_to_evr.read() {
+        trace!("Move paddle to event");
 
-        left_mouse_release_evw.write(LeftMouseReleaseEvent);
-    }
+        cursor_world_position.position = event.position;
 
-    // Mouse move
-    for event in cursor_evr.read() {
-        trace!("Mouse moved");
+        // while a ball is held, dragging aims the launch instead of moving the paddle,
+        // unless other balls are still in flight and need the paddle
+        if !held_player_balls.is_empty() && flying_player_balls.is_empty() {
+            continue;
+        }
 
-        mouse_move_evw.write(MouseMoveEvent {
-            position: event.position,
-        });
+        // the paddle is driven towards the cursor, see drive_paddle_towards_target
+        paddle_motion.target_x = event.position.x;
+        trace!("paddle target x: {}", paddle_motion.target_x)
     }
 
-    //Press Right Mouse
-    if input.just_pressed(MouseButton::Right) {
-        trace!("Right pressed");
+    for mut player_ball_transform in held_player_balls.iter_mut() {
+        trace!("Moving player ball");
 
-        right_mouse_press_evw.write(RightMousePressEvent);
+        player_ball_transform.translation.x = paddle_transform.translation.x;
     }
 }
 
 /*
-* Handle Input events in Update loop
-* Since there is only one entity affected by input (the paddle), only one system is needed for this
-*/
-fn handle_mouse_move_events(
+ * Sources without a pointer move the paddle step by step; while a ball is held, the steps move the aim sideways
+ */
+fn handle_move_paddle_by_events(
     //Singles
-    player_paddle: Single<&mut Transform, With<PlayerPaddle>>,
-    player_ball: Option<Single<&mut Transform, (With<PlayerBallInHold>, Without<PlayerPaddle>)>>,
+    player_paddle: Single<(&Transform, &Sprite, &mut PaddleMotion), With<PlayerPaddle>>,
+    // Globals
+    images: Res<Assets<Image>>,
+    playfield: Res<Playfield>,
+    mut cursor_world_position: ResMut<CursorWorldPosition>,
     // Events
-    mut mouse_move_evr: EventReader<MouseMoveEvent>,
+    mut move_paddle_by_evr: EventReader<MovePaddleByEvent>,
     // Queries
-    camera: Query<(&Camera, &GlobalTransform)>,
+    held_player_balls: Query<(), With<PlayerBallInHold>>,
+    flying_player_balls: Query<(), (With<PlayerBall>, Without<PlayerBallInHold>)>,
 ) {
-    let mut paddle_transform = player_paddle.into_inner();
+    let (paddle_transform, paddle_sprite, mut paddle_motion) = player_paddle.into_inner();
 
-    for event in mouse_move_evr.read() {
-        trace!("Move Event");
-
-        if camera.single().is_ok() {
-            if let Some((camera, camera_transform)) = camera.single().ok() {
-                let cursor_position_in_world_coord = camera.viewport_to_world_2d(
-                    camera_transform,
-                    Vec2::new(event.position.x, event.position.y),
-                );
+    for event in move_paddle_by_evr.read() {
+        trace!("Move paddle by event");
 
-                if cursor_position_in_world_coord.is_ok() {
-                    if let Some(cursor_position_in_world_coord) =
-                        cursor_position_in_world_coord.ok()
-                    {
-                        paddle_transform.translation.x = cursor_position_in_world_coord.x;
-                        enforce_paddle_borders(&mut paddle_transform);
-                        trace!("paddle translation.x: {}", paddle_transform.translation.x)
-                    }
-                }
-            }
+        if !held_player_balls.is_empty() && flying_player_balls.is_empty() {
+            cursor_world_position.position.x += event.distance;
+            continue;
         }
-    }
 
-    if let Some(player_ball) = player_ball {
-        trace!("Moving player ball");
-        let mut player_ball_transform = player_ball.into_inner();
-
-        player_ball_transform.translation.x = paddle_transform.translation.x;
+        // the target stays within the borders, so turning around takes effect at once
+        let paddle_half_width =
+            0.5 * calculate_paddle_rect(&images, paddle_sprite, paddle_transform).width();
+        paddle_motion.target_x = enforce_paddle_borders(
+            paddle_motion.target_x + event.distance,
+            &playfield,
+            paddle_half_width,
+            &paddle_motion.area,
+        );
     }
 }
 
-fn handle_left_mouse_press_events(
+fn handle_launch_press_events(
     //Singles
     player_paddle: Single<(&Transform, &mut Sprite), With<PlayerPaddle>>,
     // Globals
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+    mut cursor_world_position: ResMut<CursorWorldPosition>,
     // Events
-    mut left_mouse_press_evr: EventReader<LeftMousePressEvent>,
+    mut launch_press_evr: EventReader<LaunchPressEvent>,
     // Queries
     player_balls: Query<Entity, (With<PlayerBall>, Without<PlayerPaddle>)>,
+    mut held_player_balls: Query<(&Transform, &mut PlayerBallInHold), Without<PlayerPaddle>>,
 ) {
     let (paddle_transform, mut sprite) = player_paddle.into_inner();
 
-    for _event in left_mouse_press_evr.read() {
-        trace!("Mouse press");
+    for event in launch_press_evr.read() {
+        trace!("Launch press");
+
+        // a pointer has to press on the paddle; without one, aiming starts from the current aim position
+        if let Some(pointer) = event.pointer {
+            let paddle_rect = calculate_paddle_rect(&images, &sprite, paddle_transform);
+
+            if !paddle_rect.contains(pointer) {
+                trace!("Launch press not on paddle; ignored");
+                break;
+            }
+
+            cursor_world_position.position = pointer;
+        }
 
-        // TODO check that mouse press is on the paddle (inside paddle sprite)
         if player_balls.is_empty() {
             sprite.image = asset_server.load("paddleRed.png");
 
             commands
-                .spawn(PlayerBallBundle {
-                    marker: PlayerBall {
-                        initial_impulse: Vec2::new(0.0, 130000.0),
-                        power_level: PowerLevel::default(),
-                    },
-                    add_collider: AddCollider {
-                        collider_scale: 1.0,
-                        collider_type: ColliderType::Circle,
+                .spawn(player_ball_bundle(
+                    &asset_server,
+                    Vec2::new(
+                        paddle_transform.translation.x,
+                        paddle_transform.translation.y + BALL_HOLD_OFFSET,
+                    ),
+                ))
+                .insert((
+                    PlayerBallInHold {
+                        aim_anchor: cursor_world_position.position,
                     },
-                    sprite: Sprite::from_image(asset_server.load("ballGrey.png")),
-                    transform: Transform::from_xyz(paddle_transform.translation.x, -260.0, 0.0)
-                        .with_scale(Vec3::new(1.3, 1.3, 1.0)),
-                    rigid_body: RigidBody::Dynamic,
-                    max_linear_speed: MaxLinearSpeed(1000.0),
-                })
-                .insert(PlayerBallInHold);
+                    // a held ball must not drift away, whatever the level's gravity
+                    GravityScale(0.0),
+                ));
             debug!("PlayerBall spawned");
+
+            spawn_trajectory_preview(
+                Vec2::new(
+                    paddle_transform.translation.x,
+                    paddle_transform.translation.y + BALL_HOLD_OFFSET,
+                ),
+                &mut commands,
+                &asset_server,
+            );
+        } else {
+            // balls caught by the sticky paddle: aim from the new press position
+            for (_held_ball_transform, mut player_ball_in_hold) in held_player_balls.iter_mut() {
+                player_ball_in_hold.aim_anchor = cursor_world_position.position;
+                debug!("Held PlayerBall re-aimed");
+            }
+
+            // the preview is shown for the first held ball only
+            if let Some((held_ball_transform, _player_ball_in_hold)) =
+                held_player_balls.iter().next()
+            {
+                sprite.image = asset_server.load("paddleRed.png");
+
+                spawn_trajectory_preview(
+                    held_ball_transform.translation.truncate(),
+                    &mut commands,
+                    &asset_server,
+                );
+            }
         }
 
         break; // if there is more than one event in queue: ignore it
     }
 }
 
-fn handle_left_mouse_release_events(
+/*
+ * Updates the launch impulse of the held balls from the current drag and moves the trajectory preview dots along the predicted path of the first one
+ * Despawns the preview dots once no ball is held anymore
+ */
+fn update_launch_aim(
+    // Globals
+    mut commands: Commands,
+    cursor_world_position: Res<CursorWorldPosition>,
+    gravity: Res<Gravity>,
+    // Queries
+    mut held_player_balls: Query<(
+        &Transform,
+        &mut PlayerBall,
+        &PlayerBallInHold,
+        Option<&ComputedMass>,
+    )>,
+    mut trajectory_preview_dots: Query<
+        (Entity, &mut Transform),
+        (With<TrajectoryPreviewDot>, Without<PlayerBall>),
+    >,
+) {
+    if held_player_balls.is_empty() {
+        for (dot_entity, _dot_transform) in trajectory_preview_dots.iter() {
+            commands.entity(dot_entity).despawn();
+        }
+        return;
+    }
+
+    for (_ball_transform, mut player_ball, player_ball_in_hold, _ball_mass) in
+        held_player_balls.iter_mut()
+    {
+        player_ball.initial_impulse = calculate_launch_impulse(
+            player_ball_in_hold.aim_anchor,
+            cursor_world_position.position,
+        );
+    }
+
+    let Some((ball_transform, player_ball, _player_ball_in_hold, ball_mass)) =
+        held_player_balls.iter().next()
+    else {
+        return;
+    };
+
+    // the collider (and therefore the mass) is only added once the ball sprite is loaded
+    let Some(ball_mass) = ball_mass else {
+        return;
+    };
+    if ball_mass.value() <= 0.0 {
+        return;
+    }
+
+    let launch_velocity = player_ball.initial_impulse / ball_mass.value();
+    let launch_position = ball_transform.translation.truncate();
+
+    for (dot_index, (_dot_entity, mut dot_transform)) in
+        trajectory_preview_dots.iter_mut().enumerate()
+    {
+        let time = (dot_index + 1) as f32 * TRAJECTORY_PREVIEW_TIME_STEP;
+        let dot_position = launch_position + launch_velocity * time + 0.5 * gravity.0 * time * time;
+
+        dot_transform.translation.x = dot_position.x;
+        dot_transform.translation.y = dot_position.y;
+    }
+}
+
+fn handle_launch_release_events(
     //Singles
     player_paddle: Single<&mut Sprite, With<PlayerPaddle>>,
-    player_ball: Single<(Entity, &PlayerBall), With<PlayerBallInHold>>,
     //Globals
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     // Events
-    mut left_mouse_release_evr: EventReader<LeftMouseReleaseEvent>,
+    mut launch_release_evr: EventReader<LaunchReleaseEvent>,
+    // Queries
+    held_player_balls: Query<(Entity, &PlayerBall), With<PlayerBallInHold>>,
 ) {
     let mut sprite = player_paddle.into_inner();
-    let (player_ball_entity, player_ball) = player_ball.into_inner();
 
-    for _event in left_mouse_release_evr.read() {
+    for _event in launch_release_evr.read() {
+        if held_player_balls.is_empty() {
+            break;
+        }
+
         sprite.image = asset_server.load("paddleBlu.png");
 
-        commands
-            .entity(player_ball_entity)
-            .remove::<PlayerBallInHold>();
+        for (player_ball_entity, player_ball) in held_player_balls.iter() {
+            commands
+                .entity(player_ball_entity)
+                .remove::<(PlayerBallInHold, GravityScale)>();
 
-        commands
-            .entity(player_ball_entity)
-            .insert(ExternalImpulse::new(player_ball.initial_impulse));
+            commands
+                .entity(player_ball_entity)
+                .insert(ExternalImpulse::new(player_ball.initial_impulse));
 
-        debug!("PlayerBall launched");
+            debug!("PlayerBall launched");
+        }
 
         break; // if there is more than one event in queue: ignore it
     }
 }
 
+/*
+ * Pausing stops the virtual clock, and with it the fixed gameplay schedules and the physics
+ */
+fn handle_pause_events(
+    // Globals
+    mut virtual_time: ResMut<Time<Virtual>>,
+    // Events
+    mut pause_evr: EventReader<PauseEvent>,
+) {
+    for _event in pause_evr.read() {
+        if virtual_time.is_paused() {
+            virtual_time.unpause();
+            debug!("Game resumed");
+        } else {
+            virtual_time.pause();
+            debug!("Game paused");
+        }
+
+        break; // if there is more than one event in queue: ignore it
+    }
+}
+
+/*
+ * Despawns destroyed balls; a life is only lost when the last ball in play is lost
+ */
 fn handle_ball_destroyed_event(
-    //Singles
-    player_ball: Single<Entity, With<PlayerBall>>,
     // Globals
     mut commands: Commands,
     //Events
     mut ball_destroyed_evr: EventReader<BallDestroyedEvent>,
+    mut life_lost_evw: EventWriter<LifeLostEvent>,
+    // Queries
+    player_balls: Query<Entity, With<PlayerBall>>,
 ) {
-    let player_ball = player_ball.into_inner();
-    for _event in ball_destroyed_evr.read() {
-        commands.entity(player_ball).despawn();
-        break;
+    let mut destroyed_balls: Vec<Entity> = Vec::new();
+    let mut ball_lost = false;
+
+    for event in ball_destroyed_evr.read() {
+        // the same ball may be reported more than once (e.g. fell through and broke the physics)
+        if destroyed_balls.contains(&event.ball) || !player_balls.contains(event.ball) {
+            continue;
+        }
+
+        commands.entity(event.ball).despawn();
+        destroyed_balls.push(event.ball);
+        ball_lost |= event.ball_lost;
+    }
+
+    if ball_lost
+        && player_balls
+            .iter()
+            .all(|player_ball| destroyed_balls.contains(&player_ball))
+    {
+        life_lost_evw.write(LifeLostEvent);
+        debug!("Last ball lost; life lost event sent");
     }
 }
 
 fn player_ball_physics_sanity_check(
-    // Single
-    player_ball: Single<&Transform, With<PlayerBall>>,
     // Globals
+    playfield: Res<Playfield>,
     mut ball_destroyed_evw: EventWriter<BallDestroyedEvent>,
+    // Queries
+    player_balls: Query<(Entity, &Transform), With<PlayerBall>>,
 ) {
-    let player_ball = player_ball.into_inner();
-
-    if f32::abs(player_ball.translation.x) > 1500.0 || f32::abs(player_ball.translation.y) > 1000.0
-    {
-        ball_destroyed_evw.write(BallDestroyedEvent);
-        debug!("Ball broke the physics; destroyed event sent");
+    for (player_ball_entity, player_ball) in player_balls.iter() {
+        if playfield.is_out_of_bounds(player_ball.translation.truncate()) {
+            ball_destroyed_evw.write(BallDestroyedEvent {
+                ball: player_ball_entity,
+                ball_lost: true,
+            });
+            debug!("Ball broke the physics; destroyed event sent");
+        }
     }
 }
 
-fn handle_collision_player_ball(
-    // Single
-    player_ball: Single<(Entity, &mut PlayerBall, &mut Sprite, &mut LinearVelocity)>,
+/*
+ * Detects balls that are stuck in a loop which never reaches the paddle and nudges them out of it
+ * Loops are either long stretches with barely any vertical movement, or the same contacts repeating over and over
+ */
+fn player_ball_loop_check(
+    // Singles
+    player_paddle: Single<Entity, With<PlayerPaddle>>,
+    // Globals
+    time: Res<Time>,
+    gravity: Res<Gravity>,
     // Collisions
     collisions: Collisions,
+    // Queries
+    mut player_balls: Query<
+        (
+            Entity,
+            &Transform,
+            &mut BallLoopDetector,
+            &mut LinearVelocity,
+        ),
+        (With<PlayerBall>, Without<PlayerBallInHold>),
+    >,
+    top_colliders: Query<(), With<TopCollider>>,
 ) {
-    let (player_ball_entity, mut player_ball, mut ball_sprite, mut ball_velocity) =
-        player_ball.into_inner();
-
-    for contact_pair in collisions.iter() {
-        if contact_pair.collider1.eq(&player_ball_entity)
-            || contact_pair.collider2.eq(&player_ball_entity)
-        {
-            trace!(
-                "PlayerBall velocity at collision {:?}",
-                ball_velocity.length()
-            );
+    const LOW_VERTICAL_VELOCITY: f32 = 30.0;
+    const LOW_VERTICAL_VELOCITY_MAX_TIME: f32 = 3.0; // in seconds
+    const CONTACT_HISTORY_LENGTH: usize = 12;
 
-            let velocity_addition_factor = 10.0;
-            let velocity_addition = ball_velocity
-                .clone()
-                .normalize()
-                .mul(velocity_addition_factor);
+    let player_paddle = player_paddle.into_inner();
 
-            ball_velocity.0 = ball_velocity.0.add(velocity_addition);
-
-            trace!(
-                "PlayerBall velocity post collision {:?}",
-                ball_velocity.length()
-            );
+    for (player_ball_entity, ball_transform, mut loop_detector, mut ball_velocity) in
+        player_balls.iter_mut()
+    {
+        // low vertical velocity over time
+        if f32::abs(ball_velocity.y) < LOW_VERTICAL_VELOCITY {
+            loop_detector.low_vertical_velocity_time += time.delta_secs();
+        } else {
+            loop_detector.low_vertical_velocity_time = 0.0;
+        }
 
-            // Change ball color based on velocity
-
-            // power levels;
-            // when the ball reaches another level, color should change
-            // white = < [0]
-            // blue = [0]
-            // green = [1]
-            // yellow = [2]
-            // red = > [3]
-            let power_levels = [200.0, 400.0, 600.0, 800.0];
-
-            let ball_colors = [
-                Color::WHITE,                                      // white
-                Color::LinearRgba(LinearRgba::rgb(0.5, 0.8, 1.0)), // light blue
-                Color::LinearRgba(LinearRgba::rgb(0.0, 1.0, 0.0)), // green
-                Color::LinearRgba(LinearRgba::rgb(1.0, 1.0, 0.0)), // yellow
-                Color::LinearRgba(LinearRgba::rgb(1.0, 0.2, 0.2)), // red
-            ];
-
-            let mut power_level: usize = 0;
-            for power_level_index in 0..power_levels.len() {
-                if ball_velocity.length() < power_levels[power_level_index] {
-                    break;
+        // repeated contact sequences; only contacts that started this frame are added to the history
+        let touching_entities: Vec<Entity> = collisions
+            .iter()
+            .filter_map(|contact_pair| {
+                if contact_pair.collider1.eq(&player_ball_entity) {
+                    Some(contact_pair.collider2)
+                } else if contact_pair.collider2.eq(&player_ball_entity) {
+                    Some(contact_pair.collider1)
+                } else {
+                    None
                 }
-                power_level += 1;
+            })
+            .collect();
+
+        for touching_entity in touching_entities.iter() {
+            if loop_detector.touching_entities.contains(touching_entity) {
+                continue;
             }
 
-            match power_level {
-                0 => {
-                    player_ball.power_level = PowerLevel::Lowest;
-                }
-                1 => {
-                    player_ball.power_level = PowerLevel::Low;
+            if touching_entity.eq(&player_paddle) {
+                // the player is in control again
+                loop_detector.contact_history.clear();
+            } else {
+                loop_detector.contact_history.push(*touching_entity);
+                if loop_detector.contact_history.len() > CONTACT_HISTORY_LENGTH {
+                    loop_detector.contact_history.remove(0);
                 }
-                2 => {
-                    player_ball.power_level = PowerLevel::Medium;
-                }
-                3 => {
-                    player_ball.power_level = PowerLevel::High;
-                }
-                4 => {
-                    player_ball.power_level = PowerLevel::Highest;
-                }
-                _ => {}
             }
-
-            ball_sprite.color = ball_colors[power_level];
-            trace!("Setting ball color to {:?}", ball_sprite.color);
         }
+        loop_detector.touching_entities = touching_entities;
+
+        let stuck_horizontally =
+            loop_detector.low_vertical_velocity_time > LOW_VERTICAL_VELOCITY_MAX_TIME;
+        let stuck_in_contact_loop = contact_history_repeats(&loop_detector.contact_history);
+
+        if stuck_horizontally || stuck_in_contact_loop {
+            let nudge_direction = calculate_loop_nudge_direction(
+                ball_transform.translation.truncate(),
+                gravity.0,
+                !top_colliders.is_empty(),
+            );
+            ball_velocity.0 = calculate_loop_nudge(ball_velocity.0, nudge_direction);
 
-        break;
+            loop_detector.low_vertical_velocity_time = 0.0;
+            loop_detector.contact_history.clear();
+
+            debug!(
+                "Ball stuck in a loop (horizontal: {}, contacts: {}); nudged to {:?}",
+                stuck_horizontally, stuck_in_contact_loop, ball_velocity.0
+            );
+        }
     }
 }
 
-fn add_colliders(
-    // Globals
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    images: Res<Assets<Image>>,
-    entities_with_add_collider_tag: Query<(Entity, &Sprite, &AddCollider)>,
+fn handle_collision_player_ball(
+    // Collisions
+    collisions: Collisions,
+    // Queries
+    mut player_balls: Query<(Entity, &mut PlayerBall, &mut Sprite, &mut LinearVelocity)>,
 ) {
-    for (entity, sprite, add_collider) in entities_with_add_collider_tag {
-        if asset_server.is_loaded(&sprite.image) {
-            trace!("asset loaded, adding collider");
-
-            // make sure to remove Add Collider
-            commands.entity(entity).remove::<AddCollider>();
+    for (player_ball_entity, mut player_ball, mut ball_sprite, mut ball_velocity) in
+        player_balls.iter_mut()
+    {
+        // speed up once per frame in which the ball touches anything
+        if !collisions.iter().any(|contact_pair| {
+            contact_pair.collider1.eq(&player_ball_entity)
+                || contact_pair.collider2.eq(&player_ball_entity)
+        }) {
+            continue;
+        }
 
-            match add_collider.collider_type {
-                ColliderType::None => {}
+        trace!(
+            "PlayerBall velocity at collision {:?}",
+            ball_velocity.length()
+        );
 
-                ColliderType::Circle => {
-                    // add circle collider
-                    let collider_size = add_collider.collider_scale
-                        * (calculate_sprite_size(&images, &sprite).x * 0.5);
+        let velocity_addition_factor = 10.0;
+        let velocity_addition = ball_velocity
+            .clone()
+            .normalize()
+            .mul(velocity_addition_factor);
 
-                    commands
-                        .entity(entity)
-                        .insert(Collider::circle(collider_size));
+        ball_velocity.0 = ball_velocity.0.add(velocity_addition);
 
-                    debug!("Circle Collider created with size {}", collider_size);
-                }
+        trace!(
+            "PlayerBall velocity post collision {:?}",
+            ball_velocity.length()
+        );
 
-                ColliderType::Rectangle => {
-                    let collider_size =
-                        add_collider.collider_scale * calculate_sprite_size(&images, &sprite);
+        update_power_level(ball_velocity.length(), &mut player_ball, &mut ball_sprite);
+    }
+}
 
-                    commands
-                        .entity(entity)
-                        .insert(Collider::rectangle(collider_size.x, collider_size.y));
+/*
+ * Moves the paddle towards its target through its velocity, so the physics step sees it move
+ * and resolves its contacts with the ball, instead of the paddle teleporting into the ball
+ */
+fn drive_paddle_towards_target(
+    // Singles
+    player_paddle: Single<
+        (&Transform, &Sprite, &mut PaddleMotion, &mut LinearVelocity),
+        With<PlayerPaddle>,
+    >,
+    // Globals
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    images: Res<Assets<Image>>,
+    playfield: Res<Playfield>,
+    // Queries
+    held_player_balls: Query<(), With<PlayerBallInHold>>,
+    flying_player_balls: Query<(), (With<PlayerBall>, Without<PlayerBallInHold>)>,
+) {
+    let (paddle_transform, paddle_sprite, mut paddle_motion, mut paddle_velocity) =
+        player_paddle.into_inner();
 
-                    debug!("Rectangle Collider created with size {}", collider_size);
-                }
+    // the physics step advances by less than a fixed step while in slow motion
+    let physics_delta = time.delta_secs() * physics_time.relative_speed();
 
-                ColliderType::Capsule => {
-                    let collider_size =
-                        add_collider.collider_scale * calculate_sprite_size(&images, &sprite);
+    if physics_delta <= 0.0 {
+        return;
+    }
 
-                    commands.entity(entity).insert(Collider::capsule_endpoints(
-                        collider_size.x * 0.1,
-                        Vec2::new(collider_size.x * -0.4, 0.0),
-                        Vec2::new(collider_size.x * 0.4, 0.0),
-                    ));
-                }
+    // held balls sit on the paddle, so the paddle stays where it is while aiming;
+    // with other balls in flight it keeps moving and carries the held balls along
+    if !held_player_balls.is_empty() && flying_player_balls.is_empty() {
+        paddle_motion.target_x = paddle_transform.translation.x;
+    }
 
-                ColliderType::RegularPolygon => {
-                    let collider_size =
-                        add_collider.collider_scale * calculate_sprite_size(&images, &sprite);
+    paddle_velocity.0 = paddle_velocity_towards_target(
+        paddle_transform,
+        paddle_sprite,
+        &paddle_motion,
+        physics_delta,
+        &images,
+        &playfield,
+    );
+}
 
-                    // currently only used for a pentagon
-                    commands
-                        .entity(entity)
-                        .insert(Collider::regular_polygon(collider_size.y * 0.55, 5));
+/*
+ * A ball hitting a moving paddle picks up part of its momentum, and some spin
+ * Applies to every paddle, including player two's in co-op
+ * Runs before the ball's power level is recalculated, so a well-timed swing can power up the ball
+ */
+fn transfer_paddle_momentum(
+    // Collisions
+    collisions: Collisions,
+    // Queries
+    mut paddles: Query<(Entity, &mut PaddleMotion, &LinearVelocity), Without<PlayerBall>>,
+    mut player_balls: Query<
+        (Entity, &mut LinearVelocity, &mut AngularVelocity),
+        (With<PlayerBall>, Without<PlayerBallInHold>),
+    >,
+) {
+    for (paddle_entity, mut paddle_motion, paddle_velocity) in paddles.iter_mut() {
+        // forget balls that no longer touch the paddle, so their next contact counts again
+        paddle_motion.touching_balls.retain(|touching_ball| {
+            collisions.iter().any(|contact_pair| {
+                (contact_pair.collider1.eq(touching_ball)
+                    && contact_pair.collider2.eq(&paddle_entity))
+                    || (contact_pair.collider2.eq(touching_ball)
+                        && contact_pair.collider1.eq(&paddle_entity))
+            })
+        });
 
-                    debug!(
-                        "RegularPolygon Collider added with circumradius {} and side count {}",
-                        collider_size.y * 0.5,
-                        5
-                    );
-                }
+        for (ball_entity, mut ball_velocity, mut ball_angular_velocity) in player_balls.iter_mut() {
+            if paddle_motion.touching_balls.contains(&ball_entity) {
+                continue;
+            }
 
-                ColliderType::RoundedRectangle => {
-                    let collider_size =
-                        add_collider.collider_scale * calculate_sprite_size(&images, &sprite);
+            if !collisions.iter().any(|contact_pair| {
+                (contact_pair.collider1.eq(&ball_entity)
+                    && contact_pair.collider2.eq(&paddle_entity))
+                    || (contact_pair.collider2.eq(&ball_entity)
+                        && contact_pair.collider1.eq(&paddle_entity))
+            }) {
+                continue;
+            }
 
-                    let border_radius = 1.0;
+            paddle_motion.touching_balls.push(ball_entity);
 
-                    commands.entity(entity).insert(Collider::round_rectangle(
-                        collider_size.x,
-                        collider_size.y,
-                        border_radius,
-                    ));
+            // the solver only pushes the ball along the contact normal, the sideways swing is handed over here
+            ball_velocity.x += PADDLE_MOMENTUM_TRANSFER * paddle_velocity.x;
+            // the paddle drags the ball's underside along, so a swing to the right spins it clockwise
+            ball_angular_velocity.0 -= PADDLE_SPIN_FACTOR * paddle_velocity.x;
 
-                    debug!(
-                        "RoundRectangle Collider created with size {} and border_radius {}",
-                        collider_size, border_radius
-                    );
-                }
+            trace!(
+                "Paddle swing of {} hands momentum to the ball",
+                paddle_velocity.x
+            );
+        }
+    }
+}
 
-                ColliderType::Diamond => {
-                    let collider_size =
-                        add_collider.collider_scale * calculate_sprite_size(&images, &sprite);
+fn add_colliders(
+    // Globals
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+    entities_with_add_collider_tag: Query<(Entity, &Sprite, &AddCollider)>,
+) {
+    for (entity, sprite, add_collider) in entities_with_add_collider_tag {
+        if asset_server.is_loaded(&sprite.image) {
+            trace!("asset loaded, adding collider");
 
-                    let collider_hull_points = Vec::from([
-                        Vec2::new(0.0, collider_size.y * 0.5),
-                        Vec2::new(collider_size.x * 0.5, 0.0),
-                        Vec2::new(0.0, collider_size.y * -0.5),
-                        Vec2::new(collider_size.x * -0.5, 0.0),
-                    ]);
+            // make sure to remove Add Collider
+            commands.entity(entity).remove::<AddCollider>();
 
-                    if let Some(diamond_collider) = Collider::convex_hull(collider_hull_points) {
-                        commands.entity(entity).insert(diamond_collider);
-                    } else {
-                        debug!("Creating diamond collider failed!");
-                    }
+            let collider_size =
+                add_collider.collider_scale * calculate_sprite_size(&images, &sprite);
 
-                    // TODO actually create the collider
-                }
+            if let Some(collider) = create_collider(&add_collider.collider_type, collider_size) {
+                commands.entity(entity).insert(collider);
             }
         } else {
             trace!("Asset not yet loaded");
@@ -564,29 +865,76 @@ fn add_colliders(
 
 fn handle_collision_player_ball_and_bottom_collider(
     // Singles
-    player_ball: Single<Entity, With<PlayerBall>>,
     bottom_collider: Single<Entity, With<BottomCollider>>,
     // Collisions
     collisions: Collisions,
     // Events
     mut ball_destroyed_evw: EventWriter<BallDestroyedEvent>,
+    // Queries
+    player_balls: Query<Entity, With<PlayerBall>>,
+    top_colliders: Query<Entity, With<TopCollider>>,
 ) {
-    let player_ball = player_ball.into_inner();
     let bottom_collider = bottom_collider.into_inner();
 
-    for contact_pair in collisions.iter() {
-        // if one of the colliders is the player ball and one of them is the bottom collider
-        if (contact_pair.collider1.eq(&player_ball) || contact_pair.collider2.eq(&player_ball))
-            && (contact_pair.collider1.eq(&bottom_collider)
-                || contact_pair.collider2.eq(&bottom_collider))
-        {
-            ball_destroyed_evw.write(BallDestroyedEvent);
-            debug!("Ball fell through; sending destruction event");
-            break;
+    for player_ball in player_balls.iter() {
+        for contact_pair in collisions.iter() {
+            // if one of the colliders is the player ball and one of them is the bottom collider
+            // (or, with a mirrored paddle at the top, the top collider)
+            if (contact_pair.collider1.eq(&player_ball) || contact_pair.collider2.eq(&player_ball))
+                && (contact_pair.collider1.eq(&bottom_collider)
+                    || contact_pair.collider2.eq(&bottom_collider)
+                    || top_colliders.contains(contact_pair.collider1)
+                    || top_colliders.contains(contact_pair.collider2))
+            {
+                ball_destroyed_evw.write(BallDestroyedEvent {
+                    ball: player_ball,
+                    ball_lost: true,
+                });
+                debug!("Ball fell through; sending destruction event");
+                break;
+            }
         }
     }
 }
 
+/*
+ * Moves borders, paddle and bottom sensor whenever the playfield changes (e.g. a level with a different arena size)
+ */
+fn update_playfield_borders(
+    // Singles
+    player_paddle: Single<&mut Transform, With<PlayerPaddle>>,
+    bottom_collider: Single<
+        (&mut Transform, &mut Collider),
+        (With<BottomCollider>, Without<PlayerPaddle>),
+    >,
+    // Globals
+    playfield: Res<Playfield>,
+    // Queries
+    mut borders: Query<
+        (&PlayfieldBorder, &mut Transform),
+        (Without<PlayerPaddle>, Without<BottomCollider>),
+    >,
+) {
+    for (border, mut border_transform) in borders.iter_mut() {
+        *border_transform = playfield_border_transform(&playfield, border);
+    }
+
+    let (mut bottom_collider_transform, mut bottom_collider) = bottom_collider.into_inner();
+    bottom_collider_transform.translation.y = playfield.bottom_sensor_y();
+    *bottom_collider = Collider::rectangle(
+        playfield.bottom_sensor_size().x,
+        playfield.bottom_sensor_size().y,
+    );
+
+    let mut paddle_transform = player_paddle.into_inner();
+    paddle_transform.translation.y = playfield.paddle_y();
+
+    debug!(
+        "Playfield borders updated to {} x {}",
+        playfield.half_width, playfield.half_height
+    );
+}
+
 // Generic system that takes a component as a parameter, and will despawn all entities with that component
 fn despawn_screen<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
     for entity in &to_despawn {
@@ -619,10 +967,10 @@ struct PlayerPaddle;
 struct PermanentElement;
 
 /*
- * Marks the player ball as still being held (between ball being spawned and player launching it)
+ * Marks a dot of the launch trajectory preview; only exists while a ball is held
  */
 #[derive(Component)]
-struct PlayerBallInHold;
+struct TrajectoryPreviewDot;
 
 /*
  * Markes the bottom blocker used to determine when the ball has been "destroyed" (fell through)
@@ -656,6 +1004,39 @@ struct AddCollider {
     collider_type: ColliderType,
 }
 
+/*
+ * Marks the player ball as still being held (between ball being spawned and player launching it)
+ * The aim anchor is the world position where the player pressed on the paddle; dragging away from it aims the launch
+ */
+#[derive(Component)]
+struct PlayerBallInHold {
+    aim_anchor: Vec2,
+}
+
+/*
+ * Where the paddle is heading; it is driven there by its velocity
+ */
+#[derive(Component)]
+struct PaddleMotion {
+    target_x: f32,
+    // in pixels per second; without a maximum the paddle reaches its target within one step
+    max_speed: Option<f32>,
+    // part of the playfield the paddle may move in
+    area: PaddleArea,
+    // balls currently in contact; a ball only picks up the paddle's momentum once per contact
+    touching_balls: Vec<Entity>,
+}
+
+/*
+ * Tracks what a ball has been doing recently, to detect when it is stuck in a loop
+ */
+#[derive(Component, Default)]
+struct BallLoopDetector {
+    low_vertical_velocity_time: f32, // in seconds
+    touching_entities: Vec<Entity>,
+    contact_history: Vec<Entity>,
+}
+
 /*
  * Marks the player 'controlled' Ball entity; there might be more than one
  */
@@ -688,6 +1069,7 @@ struct PlayerPaddleBundle {
     sprite: Sprite,
     transform: Transform,
     rigid_body: RigidBody,
+    motion: PaddleMotion,
 }
 
 #[derive(Bundle)]
@@ -698,6 +1080,16 @@ struct PlayerBallBundle {
     transform: Transform,
     rigid_body: RigidBody,
     max_linear_speed: MaxLinearSpeed,
+    // fast balls would otherwise skip through thin colliders
+    swept_ccd: SweptCcd,
+    loop_detector: BallLoopDetector,
+}
+
+#[derive(Bundle)]
+struct TrajectoryPreviewDotBundle {
+    marker: TrajectoryPreviewDot,
+    sprite: Sprite,
+    transform: Transform,
 }
 
 #[derive(Bundle)]
@@ -707,6 +1099,7 @@ struct PermanentElementBundle {
     sprite: Sprite,
     transform: Transform,
     rigid_body: RigidBody,
+    border: PlayfieldBorder,
 }
 
 #[derive(Bundle)]
@@ -729,6 +1122,16 @@ struct BottomColliderBundle {
  * ================================================================================================================
  */
 
+/*
+ * Part of the playfield a paddle may move in; in co-op, each paddle may be confined to one half
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PaddleArea {
+    Full,
+    LeftHalf,
+    RightHalf,
+}
+
 /*
  * Used to define how the AddCollider Component will be interpreted
  */
@@ -744,7 +1147,17 @@ pub enum ColliderType {
     Diamond,
 }
 
-#[derive(Default, Eq, PartialEq, Ord, PartialOrd)]
+/*
+ * Which border of the playfield a permanent element forms
+ */
+#[derive(Component, Clone, Copy, Eq, PartialEq)]
+enum PlayfieldBorder {
+    Left,
+    Right,
+    Top,
+}
+
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum PowerLevel {
     #[default]
     Lowest,
@@ -766,22 +1179,20 @@ pub enum PowerLevel {
  * ================================================================================================================
  */
 
+/*
+ * Destroys the given ball; ball_lost is false when the game removes the ball (e.g. leaving a level)
+ */
 #[derive(Event)]
-struct LeftMousePressEvent;
-
-#[derive(Event)]
-struct LeftMouseReleaseEvent;
-
-#[derive(Event)]
-struct RightMousePressEvent;
-
-#[derive(Event)]
-struct MouseMoveEvent {
-    position: Vec2,
+struct BallDestroyedEvent {
+    ball: Entity,
+    ball_lost: bool,
 }
 
+/*
+ * Sent when the last ball in play has been lost
+ */
 #[derive(Event)]
-struct BallDestroyedEvent;
+struct LifeLostEvent;
 
 #[derive(Event)]
 struct ElementDestroyedEvent;
@@ -816,12 +1227,364 @@ fn calculate_sprite_size(images: &Res<Assets<Image>>, sprite: &Sprite) -> Vec2 {
     sprite_size
 }
 
-fn enforce_paddle_borders(transform: &mut Transform) {
-    const PADDLE_MAX_ABS_TRANSLATION: f32 = 520.0;
+/*
+ * Calculates the launch impulse from the drag between aim anchor and cursor
+ * Direction is limited to a cone around straight up, strength scales with drag distance; a plain click launches straight up
+ */
+fn calculate_launch_impulse(aim_anchor: Vec2, cursor_position: Vec2) -> Vec2 {
+    const AIM_DEAD_ZONE: f32 = 10.0; // in pixels
+    const AIM_MAX_DRAG: f32 = 150.0; // in pixels
+    const AIM_MAX_ANGLE: f32 = core::f32::consts::FRAC_PI_3; // 60° to either side
+
+    let mut drag = cursor_position - aim_anchor;
 
-    if f32::abs(transform.translation.x) > PADDLE_MAX_ABS_TRANSLATION {
-        transform.translation.x = PADDLE_MAX_ABS_TRANSLATION
-            * ((transform.translation.x / PADDLE_MAX_ABS_TRANSLATION) as i32) as f32;
+    if drag.length() < AIM_DEAD_ZONE {
+        return Vec2::new(0.0, LAUNCH_IMPULSE_DEFAULT);
+    }
+
+    // balls are only launched upwards: a drag below the anchor aims like the same drag above it
+    // (so dragging straight down launches straight up, instead of ending up at one side's maximum angle)
+    drag.y = f32::abs(drag.y);
+
+    // angle measured from straight up
+    let launch_angle = f32::atan2(drag.x, drag.y).clamp(-AIM_MAX_ANGLE, AIM_MAX_ANGLE);
+    // continuous at the edge of the dead zone, where the default impulse is used
+    let launch_strength =
+        ((drag.length() - AIM_DEAD_ZONE) / (AIM_MAX_DRAG - AIM_DEAD_ZONE)).clamp(0.0, 1.0);
+    let launch_impulse =
+        LAUNCH_IMPULSE_DEFAULT + (LAUNCH_IMPULSE_MAX - LAUNCH_IMPULSE_DEFAULT) * launch_strength;
+
+    Vec2::new(launch_angle.sin(), launch_angle.cos()) * launch_impulse
+}
+
+/*
+ * Calculates the area covered by the paddle sprite in world coordinates
+ */
+fn calculate_paddle_rect(
+    images: &Res<Assets<Image>>,
+    paddle_sprite: &Sprite,
+    paddle_transform: &Transform,
+) -> Rect {
+    let paddle_size =
+        calculate_sprite_size(images, paddle_sprite) * paddle_transform.scale.truncate();
+
+    Rect::from_center_size(paddle_transform.translation.truncate(), paddle_size)
+}
+
+/*
+ * Spawns the dots of the launch trajectory preview; they are positioned by update_launch_aim
+ */
+fn spawn_trajectory_preview(
+    // Parameters
+    position: Vec2,
+    // Globals
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) {
+    for _dot_index in 0..TRAJECTORY_PREVIEW_DOT_COUNT {
+        let mut dot_sprite = Sprite::from_image(asset_server.load("ballGrey.png"));
+        dot_sprite.custom_size = Some(Vec2::new(6.0, 6.0));
+        dot_sprite.color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+
+        commands.spawn(TrajectoryPreviewDotBundle {
+            marker: TrajectoryPreviewDot,
+            sprite: dot_sprite,
+            transform: Transform::from_xyz(position.x, position.y, -1.0),
+        });
+    }
+}
+
+/*
+ * Updates power level and ball color based on velocity
+ */
+fn update_power_level(ball_velocity: f32, player_ball: &mut PlayerBall, ball_sprite: &mut Sprite) {
+    // power levels;
+    // when the ball reaches another level, color should change
+    // white = < [0]
+    // blue = [0]
+    // green = [1]
+    // yellow = [2]
+    // red = > [3]
+    let ball_colors = [
+        Color::WHITE,                                      // white
+        Color::LinearRgba(LinearRgba::rgb(0.5, 0.8, 1.0)), // light blue
+        Color::LinearRgba(LinearRgba::rgb(0.0, 1.0, 0.0)), // green
+        Color::LinearRgba(LinearRgba::rgb(1.0, 1.0, 0.0)), // yellow
+        Color::LinearRgba(LinearRgba::rgb(1.0, 0.2, 0.2)), // red
+    ];
+
+    let mut power_level: usize = 0;
+    for power_level_index in 0..POWER_LEVEL_THRESHOLDS.len() {
+        if ball_velocity < POWER_LEVEL_THRESHOLDS[power_level_index] {
+            break;
+        }
+        power_level += 1;
+    }
+
+    match power_level {
+        0 => {
+            player_ball.power_level = PowerLevel::Lowest;
+        }
+        1 => {
+            player_ball.power_level = PowerLevel::Low;
+        }
+        2 => {
+            player_ball.power_level = PowerLevel::Medium;
+        }
+        3 => {
+            player_ball.power_level = PowerLevel::High;
+        }
+        4 => {
+            player_ball.power_level = PowerLevel::Highest;
+        }
+        _ => {}
+    }
+
+    ball_sprite.color = ball_colors[power_level];
+    trace!("Setting ball color to {:?}", ball_sprite.color);
+}
+
+/*
+ * Returns the velocity needed to reach the next power level, if there is one
+ */
+fn next_power_level_threshold(ball_velocity: f32) -> Option<f32> {
+    POWER_LEVEL_THRESHOLDS
+        .iter()
+        .copied()
+        .find(|threshold| ball_velocity < *threshold)
+}
+
+/*
+ * Assembles a new player ball at the given position; used for the ball on the paddle and for additional balls
+ */
+fn player_ball_bundle(asset_server: &Res<AssetServer>, position: Vec2) -> PlayerBallBundle {
+    PlayerBallBundle {
+        marker: PlayerBall {
+            initial_impulse: Vec2::new(0.0, LAUNCH_IMPULSE_DEFAULT),
+            power_level: PowerLevel::default(),
+        },
+        add_collider: AddCollider {
+            collider_scale: 1.0,
+            collider_type: ColliderType::Circle,
+        },
+        sprite: Sprite::from_image(asset_server.load("ballGrey.png")),
+        transform: Transform::from_xyz(position.x, position.y, 0.0).with_scale(Vec3::new(
+            PLAYER_BALL_SCALE,
+            PLAYER_BALL_SCALE,
+            1.0,
+        )),
+        rigid_body: RigidBody::Dynamic,
+        max_linear_speed: MaxLinearSpeed(PLAYER_BALL_MAX_SPEED),
+        swept_ccd: SweptCcd::default(),
+        loop_detector: BallLoopDetector::default(),
+    }
+}
+
+/*
+ * Returns true if the contact history ends in the same short sequence repeated three times
+ */
+fn contact_history_repeats(contact_history: &[Entity]) -> bool {
+    const MAX_LOOP_LENGTH: usize = 4;
+    const LOOP_REPETITIONS: usize = 3;
+
+    for loop_length in 1..=MAX_LOOP_LENGTH {
+        let checked_length = loop_length * LOOP_REPETITIONS;
+        if contact_history.len() < checked_length {
+            break;
+        }
+
+        let checked_contacts = &contact_history[contact_history.len() - checked_length..];
+        if (loop_length..checked_length)
+            .all(|index| checked_contacts[index] == checked_contacts[index - loop_length])
+        {
+            return true;
+        }
+    }
+
+    false
+}
+
+/*
+ * Direction in which a stuck ball is nudged: along the gravity, which brings it down to the paddle anyway;
+ * without gravity, towards the nearer paddle (the top one only exists with a mirrored paddle at the top)
+ */
+fn calculate_loop_nudge_direction(ball_position: Vec2, gravity: Vec2, paddle_at_top: bool) -> Vec2 {
+    if gravity != Vec2::ZERO {
+        gravity.normalize()
+    } else if paddle_at_top && ball_position.y > 0.0 {
+        Vec2::Y
+    } else {
+        Vec2::NEG_Y
+    }
+}
+
+/*
+ * Rotates the velocity by a small fixed angle towards the nudge direction; the speed (and therefore the power level)
+ * is kept
+ */
+fn calculate_loop_nudge(ball_velocity: Vec2, nudge_direction: Vec2) -> Vec2 {
+    const NUDGE_ANGLE: f32 = 10.0 * core::f32::consts::PI / 180.0;
+
+    // counter-clockwise if the nudge direction lies to the left of the velocity, clockwise otherwise
+    let nudge_angle = if ball_velocity.perp_dot(nudge_direction) >= 0.0 {
+        NUDGE_ANGLE
+    } else {
+        -NUDGE_ANGLE
+    };
+
+    Vec2::from_angle(nudge_angle).rotate(ball_velocity)
+}
+
+/*
+ * Creates the collider for a (scaled) sprite size; returns None for ColliderType::None
+ */
+fn create_collider(collider_type: &ColliderType, collider_size: Vec2) -> Option<Collider> {
+    match collider_type {
+        ColliderType::None => None,
+
+        ColliderType::Circle => {
+            let collider_radius = collider_size.x * 0.5;
+
+            debug!("Circle Collider created with size {}", collider_radius);
+            Some(Collider::circle(collider_radius))
+        }
+
+        ColliderType::Rectangle => {
+            debug!("Rectangle Collider created with size {}", collider_size);
+            Some(Collider::rectangle(collider_size.x, collider_size.y))
+        }
+
+        ColliderType::Capsule => Some(Collider::capsule_endpoints(
+            collider_size.x * 0.1,
+            Vec2::new(collider_size.x * -0.4, 0.0),
+            Vec2::new(collider_size.x * 0.4, 0.0),
+        )),
+
+        ColliderType::RegularPolygon => {
+            // currently only used for a pentagon
+            debug!(
+                "RegularPolygon Collider added with circumradius {} and side count {}",
+                collider_size.y * 0.55,
+                5
+            );
+            Some(Collider::regular_polygon(collider_size.y * 0.55, 5))
+        }
+
+        ColliderType::RoundedRectangle => {
+            let border_radius = 1.0;
+
+            debug!(
+                "RoundRectangle Collider created with size {} and border_radius {}",
+                collider_size, border_radius
+            );
+            Some(Collider::round_rectangle(
+                collider_size.x,
+                collider_size.y,
+                border_radius,
+            ))
+        }
+
+        ColliderType::Diamond => {
+            let collider_hull_points = Vec::from([
+                Vec2::new(0.0, collider_size.y * 0.5),
+                Vec2::new(collider_size.x * 0.5, 0.0),
+                Vec2::new(0.0, collider_size.y * -0.5),
+                Vec2::new(collider_size.x * -0.5, 0.0),
+            ]);
+
+            let diamond_collider = Collider::convex_hull(collider_hull_points);
+            if diamond_collider.is_none() {
+                debug!("Creating diamond collider failed!");
+            }
+            diamond_collider
+        }
+    }
+}
+
+/*
+ * Sets gravity, restitution and friction for all bodies without their own values
+ */
+fn apply_physics_rules(
+    physics_rules: &PhysicsRules,
+    gravity: &mut Gravity,
+    default_restitution: &mut DefaultRestitution,
+    default_friction: &mut DefaultFriction,
+) {
+    gravity.0 = physics_rules.gravity;
+
+    default_restitution.coefficient = physics_rules.restitution;
+    default_restitution.combine_rule = CoefficientCombine::Max;
+
+    default_friction.dynamic_coefficient = physics_rules.friction;
+
+    debug!(
+        "Physics rules applied: gravity {}, restitution {}, friction {}",
+        physics_rules.gravity, physics_rules.restitution, physics_rules.friction
+    );
+}
+
+fn enforce_paddle_borders(
+    x: f32,
+    playfield: &Playfield,
+    paddle_half_width: f32,
+    paddle_area: &PaddleArea,
+) -> f32 {
+    let paddle_max_abs_translation = playfield.paddle_max_abs_x(paddle_half_width);
+    // a paddle confined to a half must not reach over the center line
+    let paddle_min_abs_translation = paddle_half_width.min(paddle_max_abs_translation);
+
+    match paddle_area {
+        PaddleArea::Full => x.clamp(-paddle_max_abs_translation, paddle_max_abs_translation),
+        PaddleArea::LeftHalf => x.clamp(-paddle_max_abs_translation, -paddle_min_abs_translation),
+        PaddleArea::RightHalf => x.clamp(paddle_min_abs_translation, paddle_max_abs_translation),
+    }
+}
+
+/*
+ * Velocity that brings a paddle to its target (within its area) in one physics step, up to its maximum speed
+ */
+fn paddle_velocity_towards_target(
+    // Parameters
+    paddle_transform: &Transform,
+    paddle_sprite: &Sprite,
+    paddle_motion: &PaddleMotion,
+    physics_delta: f32,
+    // Globals
+    images: &Res<Assets<Image>>,
+    playfield: &Playfield,
+) -> Vec2 {
+    let paddle_half_width =
+        0.5 * calculate_paddle_rect(images, paddle_sprite, paddle_transform).width();
+    let target_x = enforce_paddle_borders(
+        paddle_motion.target_x,
+        playfield,
+        paddle_half_width,
+        &paddle_motion.area,
+    );
+
+    let mut velocity_x = (target_x - paddle_transform.translation.x) / physics_delta;
+    if let Some(max_speed) = paddle_motion.max_speed {
+        velocity_x = velocity_x.clamp(-max_speed, max_speed);
+    }
+
+    Vec2::new(velocity_x, 0.0)
+}
+
+/*
+ * Position, rotation and length of a playfield border; the border sprite lies horizontally
+ */
+fn playfield_border_transform(playfield: &Playfield, border: &PlayfieldBorder) -> Transform {
+    use core::f32::consts::FRAC_PI_2; // quarter turn = 90°
+
+    match border {
+        PlayfieldBorder::Left => Transform::from_xyz(-playfield.half_width, 0.0, 0.0)
+            .with_rotation(Quat::from_rotation_z(FRAC_PI_2))
+            .with_scale(Vec3::new(playfield.side_border_scale(), 1.0, 1.0)),
+        PlayfieldBorder::Right => Transform::from_xyz(playfield.half_width, 0.0, 0.0)
+            .with_rotation(Quat::from_rotation_z(-FRAC_PI_2))
+            .with_scale(Vec3::new(playfield.side_border_scale(), 1.0, 1.0)),
+        PlayfieldBorder::Top => Transform::from_xyz(0.0, playfield.half_height, 0.0)
+            .with_scale(Vec3::new(playfield.top_border_scale(), 1.0, 1.0)),
     }
 }
 
@@ -837,6 +1600,23 @@ fn enforce_paddle_borders(transform: &mut Transform) {
  * ================================================================================================================
  */
 
+/*
+ * Order of the gameplay systems within the fixed schedules
+ */
+#[derive(SystemSet, Clone, Copy, Eq, PartialEq, Debug, Hash)]
+enum GameplaySet {
+    // input events turned into paddle and ball actions (FixedUpdate, before the physics step)
+    Input,
+    // moving elements driven along their paths (FixedUpdate, before the physics step)
+    ElementMovement,
+    // ball speed and power level after the physics step (FixedPostUpdate)
+    BallPhysics,
+    // reactions of elements, sensors and the paddle to ball contacts
+    CollisionReaction,
+    // handling of the destruction and progress events sent by the reactions
+    Events,
+}
+
 // State used for the current menu screen
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
 enum GameState {
@@ -858,6 +1638,153 @@ enum GameState {
  * ================================================================================================================
  */
 
+/*
+ * Small deterministic random number generator (xorshift); identical seeds give identical games
+ */
+#[derive(Resource)]
+struct GameRng {
+    state: u64,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng {
+            state: 0x2545_F491_4F6C_DD1D,
+        }
+    }
+}
+
+impl GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        (self.state >> 32) as u32
+    }
+
+    /*
+     * Returns a value in [0, 1)
+     */
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
+
+/*
+ * Size of the arena; borders, paddle limits, the bottom sensor and out-of-bounds checks are all derived from it
+ */
+#[derive(Resource, Clone, PartialEq)]
+pub struct Playfield {
+    // distance from the center to the center line of the side borders
+    pub half_width: f32,
+    // distance from the center to the center line of the top border
+    pub half_height: f32,
+}
+
+impl Default for Playfield {
+    fn default() -> Self {
+        Playfield {
+            half_width: 615.0,
+            half_height: 335.0,
+        }
+    }
+}
+
+impl Playfield {
+    // size of the buttonSelected sprite the borders are made of
+    const BORDER_SPRITE_LENGTH: f32 = 190.0;
+    const BORDER_THICKNESS: f32 = 49.0;
+    // distance from the top border to the paddle, and from the paddle to the bottom sensor
+    const PADDLE_DISTANCE_FROM_BOTTOM: f32 = 35.0;
+    const BOTTOM_SENSOR_DISTANCE: f32 = 50.0;
+    const BOTTOM_SENSOR_HEIGHT: f32 = 60.0;
+    // how far the bottom sensor reaches past each side border, so no ball falls past its ends
+    const BOTTOM_SENSOR_SIDE_MARGIN: f32 = 25.0;
+    // distance from the paddle down to the shield, and the shield's thickness
+    const SHIELD_DISTANCE_BELOW_PADDLE: f32 = 30.0;
+    const SHIELD_THICKNESS: f32 = 12.0;
+    // a ball this far outside the borders has broken the physics
+    const OUT_OF_BOUNDS_MARGIN: f32 = 300.0;
+
+    fn inner_half_width(&self) -> f32 {
+        self.half_width - 0.5 * Self::BORDER_THICKNESS
+    }
+
+    fn inner_half_height(&self) -> f32 {
+        self.half_height - 0.5 * Self::BORDER_THICKNESS
+    }
+
+    fn side_border_scale(&self) -> f32 {
+        (2.0 * self.half_height + Self::BORDER_THICKNESS) / Self::BORDER_SPRITE_LENGTH
+    }
+
+    fn top_border_scale(&self) -> f32 {
+        (2.0 * self.half_width - Self::BORDER_THICKNESS) / Self::BORDER_SPRITE_LENGTH
+    }
+
+    fn paddle_y(&self) -> f32 {
+        -self.half_height + Self::PADDLE_DISTANCE_FROM_BOTTOM
+    }
+
+    fn paddle_max_abs_x(&self, paddle_half_width: f32) -> f32 {
+        (self.inner_half_width() - paddle_half_width).max(0.0)
+    }
+
+    fn shield_y(&self) -> f32 {
+        self.paddle_y() - Self::SHIELD_DISTANCE_BELOW_PADDLE
+    }
+
+    fn shield_size(&self) -> Vec2 {
+        Vec2::new(2.0 * self.inner_half_width(), Self::SHIELD_THICKNESS)
+    }
+
+    fn bottom_sensor_y(&self) -> f32 {
+        -self.half_height - Self::BOTTOM_SENSOR_DISTANCE
+    }
+
+    fn bottom_sensor_size(&self) -> Vec2 {
+        Vec2::new(
+            2.0 * (self.half_width + Self::BOTTOM_SENSOR_SIDE_MARGIN),
+            Self::BOTTOM_SENSOR_HEIGHT,
+        )
+    }
+
+    fn is_out_of_bounds(&self, position: Vec2) -> bool {
+        f32::abs(position.x) > self.half_width + Self::OUT_OF_BOUNDS_MARGIN
+            || f32::abs(position.y) > self.half_height + Self::OUT_OF_BOUNDS_MARGIN
+    }
+}
+
+/*
+ * Global physics settings; levels may override them
+ */
+#[derive(Clone, PartialEq)]
+pub struct PhysicsRules {
+    pub gravity: Vec2,
+    // restitution and friction of all colliders that do not define their own
+    pub restitution: f32,
+    pub friction: f32,
+}
+
+impl Default for PhysicsRules {
+    fn default() -> Self {
+        PhysicsRules {
+            gravity: Vec2::new(0.0, -1.0),
+            restitution: 1.0,
+            friction: 0.0,
+        }
+    }
+}
+
+/*
+ * Last known cursor position in world coordinates
+ */
+#[derive(Resource, Default)]
+struct CursorWorldPosition {
+    position: Vec2,
+}
+
 /*
  * ================================================================================================================
  * END - Resource
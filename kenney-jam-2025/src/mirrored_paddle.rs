@@ -0,0 +1,277 @@
+/*
+ * Plugin to handle the mirrored second paddle some levels use
+ * The second paddle copies the player's paddle across one or both axes of the playfield;
+ * when it sits at the top, the top border turns into a second loss sensor
+ */
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    AddCollider, ColliderType, GameState, GameplaySet, PaddleArea, PaddleMotion, PlayerPaddle,
+    Playfield, PlayfieldBorder, drive_paddle_towards_target,
+};
+
+use crate::levels::{LevelRules, OnLevelsScreen, level_setup};
+
+/*
+ * Plugin defintion
+ */
+pub fn mirrored_paddle_plugin(app: &mut App) {
+    app
+        // When entering the state, spawn the second paddle once the level's rules are known
+        .add_systems(
+            OnEnter(GameState::Levels),
+            spawn_mirrored_paddle.after(level_setup),
+        )
+        // While in this state, let the second paddle follow the player's paddle
+        .add_systems(
+            FixedUpdate,
+            move_mirrored_paddle
+                .after(drive_paddle_towards_target)
+                .in_set(GameplaySet::Input)
+                .run_if(in_state(GameState::Levels).and(any_with_component::<MirroredPaddle>)),
+        )
+        // When exiting the state, bring back the top border (the paddle and sensor are despawned with the screen)
+        .add_systems(OnExit(GameState::Levels), restore_top_border);
+}
+
+/*
+ * ================================================================================================================
+ * START - Plugin Systems
+ * ================================================================================================================
+ */
+
+fn spawn_mirrored_paddle(
+    // Singles
+    player_paddle: Single<&Transform, With<PlayerPaddle>>,
+    // Globals
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_rules: Res<LevelRules>,
+    playfield: Res<Playfield>,
+    // Queries
+    borders: Query<(Entity, &PlayfieldBorder)>,
+) {
+    let Some(paddle_mirror) = level_rules.mirrored_paddle else {
+        return;
+    };
+
+    let paddle_transform = player_paddle.into_inner();
+
+    let mut sprite = Sprite::from_image(asset_server.load("paddleBlu.png"));
+    let mut paddle_y = paddle_transform.translation.y;
+
+    if paddle_mirror.is_at_top() {
+        sprite.flip_y = true;
+        paddle_y = -paddle_y;
+
+        // the top is now guarded by a paddle, so a ball getting past it is lost
+        for (border_entity, border) in borders.iter() {
+            if *border == PlayfieldBorder::Top {
+                commands
+                    .entity(border_entity)
+                    .insert((ColliderDisabled, Visibility::Hidden));
+            }
+        }
+
+        commands.spawn(TopColliderBundle {
+            marker: TopCollider,
+            screen_marker: OnLevelsScreen,
+            collider: Collider::rectangle(
+                playfield.bottom_sensor_size().x,
+                playfield.bottom_sensor_size().y,
+            ),
+            transform: Transform::from_xyz(0.0, -playfield.bottom_sensor_y(), 0.0),
+            rigid_body: RigidBody::Static,
+        });
+    }
+
+    let mirrored_x = paddle_mirror.mirror_x(paddle_transform.translation.x);
+
+    commands.spawn(MirroredPaddleBundle {
+        marker: MirroredPaddle {
+            mirror: paddle_mirror,
+        },
+        screen_marker: OnLevelsScreen,
+        add_collider: AddCollider {
+            collider_scale: 1.0,
+            collider_type: ColliderType::Capsule,
+        },
+        sprite,
+        transform: paddle_transform.with_translation(Vec3::new(mirrored_x, paddle_y, 0.0)),
+        rigid_body: RigidBody::Kinematic,
+        // driven by move_mirrored_paddle; the motion lets balls pick up its swing like the player's paddle's
+        motion: PaddleMotion {
+            target_x: mirrored_x,
+            max_speed: None,
+            area: PaddleArea::Full,
+            touching_balls: Vec::new(),
+        },
+    });
+
+    debug!("Mirrored paddle spawned ({:?})", paddle_mirror);
+}
+
+/*
+ * Drives the second paddle to the mirror image of where the player's paddle will be after the physics step
+ */
+fn move_mirrored_paddle(
+    // Singles
+    player_paddle: Single<(&Transform, &LinearVelocity), With<PlayerPaddle>>,
+    mirrored_paddle: Single<
+        (
+            &MirroredPaddle,
+            &mut Transform,
+            &mut PaddleMotion,
+            &mut LinearVelocity,
+        ),
+        Without<PlayerPaddle>,
+    >,
+    // Globals
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+) {
+    let (paddle_transform, paddle_velocity) = player_paddle.into_inner();
+    let (mirrored_paddle, mut mirrored_transform, mut mirrored_motion, mut mirrored_velocity) =
+        mirrored_paddle.into_inner();
+
+    let physics_delta = time.delta_secs() * physics_time.relative_speed();
+
+    if physics_delta <= 0.0 {
+        return;
+    }
+
+    let next_paddle_x = paddle_transform.translation.x + paddle_velocity.x * physics_delta;
+    let target_x = mirrored_paddle.mirror.mirror_x(next_paddle_x);
+    mirrored_motion.target_x = target_x;
+
+    mirrored_velocity.0 = Vec2::new(
+        (target_x - mirrored_transform.translation.x) / physics_delta,
+        0.0,
+    );
+
+    // e.g. the wider paddle power-up
+    if mirrored_transform.scale.x != paddle_transform.scale.x {
+        mirrored_transform.scale.x = paddle_transform.scale.x;
+    }
+}
+
+fn restore_top_border(
+    // Globals
+    mut commands: Commands,
+    // Queries
+    borders: Query<(Entity, &PlayfieldBorder)>,
+) {
+    for (border_entity, border) in borders.iter() {
+        if *border == PlayfieldBorder::Top {
+            commands
+                .entity(border_entity)
+                .remove::<ColliderDisabled>()
+                .insert(Visibility::Inherited);
+        }
+    }
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Components
+ * ================================================================================================================
+ */
+
+/*
+ * Marks the second paddle; it is not a PlayerPaddle, since it never holds or launches balls
+ * Otherwise it plays like the player's paddle: it hands its swing to the ball and ends combos
+ */
+#[derive(Component)]
+struct MirroredPaddle {
+    mirror: PaddleMirror,
+}
+
+/*
+ * Marks the loss sensor replacing the top border, the counterpart of the BottomCollider
+ */
+#[derive(Component)]
+pub struct TopCollider;
+
+/*
+ * ================================================================================================================
+ * END - Plugin Components
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Bundles
+ * ================================================================================================================
+ */
+#[derive(Bundle)]
+struct MirroredPaddleBundle {
+    marker: MirroredPaddle,
+    screen_marker: OnLevelsScreen,
+    add_collider: AddCollider,
+    sprite: Sprite,
+    transform: Transform,
+    rigid_body: RigidBody,
+    motion: PaddleMotion,
+}
+
+#[derive(Bundle)]
+struct TopColliderBundle {
+    marker: TopCollider,
+    screen_marker: OnLevelsScreen,
+    collider: Collider,
+    transform: Transform,
+    rigid_body: RigidBody,
+}
+/*
+ * ================================================================================================================
+ * END - Plugin Bundles
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Enumerations
+ * ================================================================================================================
+ */
+
+/*
+ * Where the second paddle sits, relative to the player's paddle
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PaddleMirror {
+    // at the top, right above the player's paddle
+    Horizontal,
+    // at the bottom, on the opposite side, moving inversely
+    Vertical,
+    // at the top, on the opposite side, moving inversely
+    Central,
+}
+
+impl PaddleMirror {
+    pub fn is_at_top(&self) -> bool {
+        match self {
+            PaddleMirror::Horizontal | PaddleMirror::Central => true,
+            PaddleMirror::Vertical => false,
+        }
+    }
+
+    pub fn mirror_x(&self, x: f32) -> f32 {
+        match self {
+            PaddleMirror::Horizontal => x,
+            PaddleMirror::Vertical | PaddleMirror::Central => -x,
+        }
+    }
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Enumerations
+ * ================================================================================================================
+ */
@@ -0,0 +1,633 @@
+/*
+ * Plugin to handle power-ups dropped by destroyed blocks
+ */
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    BALL_HOLD_OFFSET, CursorWorldPosition, GameRng, GameState, GameplaySet, PADDLE_BASE_SCALE_X,
+    PlayerBall, PlayerBallInHold, PlayerPaddle, Playfield, calculate_paddle_rect,
+    next_power_level_threshold, player_ball_bundle, update_power_level,
+};
+
+use crate::levels::{BlockDestroyedEvent, OnLevelsScreen};
+use crate::shield::ShieldGrantedEvent;
+
+/*
+ * Plugin defintion
+ */
+pub fn power_ups_plugin(app: &mut App) {
+    app
+        // While in the levels state, move, collect and apply power-ups before the physics step
+        .add_systems(
+            FixedUpdate,
+            (
+                move_power_up_pickups,
+                handle_power_up_collected_event,
+                tick_active_power_ups,
+                apply_active_power_ups,
+            )
+                .chain()
+                .in_set(GameplaySet::Input)
+                .run_if(in_state(GameState::Levels)),
+        )
+        // and react to ball contacts and destroyed blocks after it
+        .add_systems(
+            FixedPostUpdate,
+            (
+                handle_collision_player_ball_and_sticky_paddle
+                    .in_set(GameplaySet::CollisionReaction),
+                spawn_power_up_on_block_destroyed.in_set(GameplaySet::Events),
+            )
+                .run_if(in_state(GameState::Levels)),
+        )
+        // When exiting the state, remove all effects (pickups are despawned with the levels screen)
+        .add_systems(OnExit(GameState::Levels), reset_power_ups)
+        // Events
+        .add_event::<PowerUpCollectedEvent>()
+        // Resources
+        .init_resource::<ActivePowerUps>();
+}
+
+/*
+ * ================================================================================================================
+ * START - Power-Up Definitions
+ * ================================================================================================================
+ */
+
+// chance that a destroyed block drops a power-up
+const POWER_UP_DROP_CHANCE: f32 = 0.2;
+// falling speed of a pickup, in pixels per second
+const POWER_UP_FALL_SPEED: f32 = 150.0;
+
+const SPEED_BOOST_FACTOR: f32 = 1.25;
+const MULTI_BALL_SPREAD_ANGLE: f32 = core::f32::consts::FRAC_PI_6; // 30° to either side
+// sideways distance of the extra balls from the split ball; a bit more than a ball's diameter, so none start overlapped
+const MULTI_BALL_SPAWN_OFFSET: f32 = 32.0;
+const WIDER_PADDLE_SCALE_PER_STACK: f32 = 0.3;
+const SLOW_MOTION_RELATIVE_SPEED: f32 = 0.6;
+const SHIELD_HITS_PER_PICKUP: u32 = 2;
+
+/*
+ * Every power-up kind with its sprite, drop chance and effect duration
+ * Instant power-ups are applied once on pickup and have no duration
+ */
+static POWER_UP_DEFINITIONS: [PowerUpDefinition; 7] = [
+    PowerUpDefinition {
+        kind: PowerUpKind::SpeedBoost,
+        asset_path: "element_yellow_polygon_glossy.png",
+        drop_weight: 3,
+        duration: 0.0,
+        stacking: PowerUpStacking::Instant,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::PowerLevelUp,
+        asset_path: "element_red_polygon_glossy.png",
+        drop_weight: 2,
+        duration: 0.0,
+        stacking: PowerUpStacking::Instant,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::MultiBall,
+        asset_path: "element_purple_cube_glossy.png",
+        drop_weight: 1,
+        duration: 0.0,
+        stacking: PowerUpStacking::Instant,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::WiderPaddle,
+        asset_path: "element_purple_rectangle_glossy.png",
+        drop_weight: 2,
+        duration: 10.0,
+        stacking: PowerUpStacking::Stack { max_stacks: 2 },
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::SlowMotion,
+        asset_path: "element_blue_polygon_glossy.png",
+        drop_weight: 2,
+        duration: 5.0,
+        stacking: PowerUpStacking::Refresh,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::StickyPaddle,
+        asset_path: "element_green_polygon_glossy.png",
+        drop_weight: 1,
+        duration: 10.0,
+        stacking: PowerUpStacking::Extend,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::Shield,
+        asset_path: "element_purple_diamond_glossy.png",
+        drop_weight: 1,
+        duration: 0.0,
+        stacking: PowerUpStacking::Instant,
+    },
+];
+
+/*
+ * ================================================================================================================
+ * END - Power-Up Definitions
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Systems
+ * ================================================================================================================
+ */
+
+fn spawn_power_up_on_block_destroyed(
+    // Globals
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut game_rng: ResMut<GameRng>,
+    // Events
+    mut block_destroyed_evr: EventReader<BlockDestroyedEvent>,
+) {
+    for event in block_destroyed_evr.read() {
+        if game_rng.next_f32() >= POWER_UP_DROP_CHANCE {
+            continue;
+        }
+
+        let definition = roll_power_up_definition(&mut game_rng);
+
+        let mut sprite = Sprite::from_image(asset_server.load(definition.asset_path));
+        sprite.custom_size = Some(Vec2::new(24.0, 24.0));
+
+        commands.spawn(PowerUpPickupBundle {
+            marker: PowerUpPickup {
+                kind: definition.kind,
+            },
+            screen_marker: OnLevelsScreen,
+            sprite,
+            transform: Transform::from_xyz(event.position.x, event.position.y, 1.0),
+        });
+
+        debug!("Power-up {:?} dropped", definition.kind);
+    }
+}
+
+/*
+ * Lets pickups fall; pickups touching the paddle are collected, pickups below the paddle are lost
+ */
+fn move_power_up_pickups(
+    // Singles
+    player_paddle: Single<(&Transform, &Sprite), With<PlayerPaddle>>,
+    // Globals
+    mut commands: Commands,
+    time: Res<Time>,
+    images: Res<Assets<Image>>,
+    playfield: Res<Playfield>,
+    // Events
+    mut power_up_collected_evw: EventWriter<PowerUpCollectedEvent>,
+    // Queries
+    mut power_up_pickups: Query<(Entity, &PowerUpPickup, &mut Transform), Without<PlayerPaddle>>,
+) {
+    let (paddle_transform, paddle_sprite) = player_paddle.into_inner();
+    let paddle_rect = calculate_paddle_rect(&images, paddle_sprite, paddle_transform);
+
+    for (pickup_entity, pickup, mut pickup_transform) in power_up_pickups.iter_mut() {
+        pickup_transform.translation.y -= POWER_UP_FALL_SPEED * time.delta_secs();
+
+        if paddle_rect.contains(pickup_transform.translation.truncate()) {
+            commands.entity(pickup_entity).despawn();
+            power_up_collected_evw.write(PowerUpCollectedEvent { kind: pickup.kind });
+            debug!("Power-up {:?} collected", pickup.kind);
+        } else if pickup_transform.translation.y < playfield.bottom_sensor_y() {
+            // below the paddle, can no longer be caught
+            commands.entity(pickup_entity).despawn();
+            trace!("Power-up {:?} lost", pickup.kind);
+        }
+    }
+}
+
+fn handle_power_up_collected_event(
+    // Globals
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+    // Events
+    mut power_up_collected_evr: EventReader<PowerUpCollectedEvent>,
+    mut shield_granted_evw: EventWriter<ShieldGrantedEvent>,
+    // Queries
+    mut player_balls: Query<
+        (
+            &Transform,
+            &mut PlayerBall,
+            &mut Sprite,
+            &mut LinearVelocity,
+        ),
+        Without<PlayerBallInHold>,
+    >,
+) {
+    for event in power_up_collected_evr.read() {
+        match event.kind {
+            PowerUpKind::SpeedBoost => {
+                for (_transform, mut player_ball, mut ball_sprite, mut ball_velocity) in
+                    player_balls.iter_mut()
+                {
+                    ball_velocity.0 *= SPEED_BOOST_FACTOR;
+                    update_power_level(ball_velocity.length(), &mut player_ball, &mut ball_sprite);
+                }
+            }
+
+            PowerUpKind::PowerLevelUp => {
+                for (_transform, mut player_ball, mut ball_sprite, mut ball_velocity) in
+                    player_balls.iter_mut()
+                {
+                    if let Some(threshold) = next_power_level_threshold(ball_velocity.length()) {
+                        ball_velocity.0 = ball_velocity.normalize_or_zero() * (threshold + 1.0);
+                        update_power_level(
+                            ball_velocity.length(),
+                            &mut player_ball,
+                            &mut ball_sprite,
+                        );
+                    }
+                }
+            }
+
+            PowerUpKind::MultiBall => {
+                // split only the first ball in play, otherwise the number of balls would grow exponentially
+                if let Some((ball_transform, _player_ball, _ball_sprite, ball_velocity)) =
+                    player_balls.iter().next()
+                {
+                    // each extra ball starts on the side it flies off to
+                    let sideways = ball_velocity.normalize_or(Vec2::Y).perp();
+
+                    for spread_angle in [-MULTI_BALL_SPREAD_ANGLE, MULTI_BALL_SPREAD_ANGLE] {
+                        commands
+                            .spawn(player_ball_bundle(
+                                &asset_server,
+                                ball_transform.translation.truncate()
+                                    + sideways * spread_angle.signum() * MULTI_BALL_SPAWN_OFFSET,
+                            ))
+                            .insert(LinearVelocity(
+                                Vec2::from_angle(spread_angle).rotate(ball_velocity.0),
+                            ));
+                    }
+                    debug!("Additional balls spawned");
+                }
+            }
+
+            PowerUpKind::Shield => {
+                shield_granted_evw.write(ShieldGrantedEvent {
+                    hits: SHIELD_HITS_PER_PICKUP,
+                });
+            }
+
+            PowerUpKind::WiderPaddle | PowerUpKind::SlowMotion | PowerUpKind::StickyPaddle => {}
+        }
+
+        activate_power_up(&mut active_power_ups, power_up_definition(event.kind));
+    }
+}
+
+fn tick_active_power_ups(
+    // Globals
+    time: Res<Time>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+) {
+    for active_power_up in active_power_ups.active_power_ups.iter_mut() {
+        active_power_up.remaining_duration -= time.delta_secs();
+    }
+
+    active_power_ups.active_power_ups.retain(|active_power_up| {
+        if active_power_up.remaining_duration > 0.0 {
+            true
+        } else {
+            debug!("Power-up {:?} expired", active_power_up.kind);
+            false
+        }
+    });
+}
+
+/*
+ * Applies the lasting effects (paddle width, physics speed) of the currently active power-ups
+ */
+fn apply_active_power_ups(
+    // Singles
+    player_paddle: Single<&mut Transform, With<PlayerPaddle>>,
+    // Globals
+    active_power_ups: Res<ActivePowerUps>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    let mut paddle_transform = player_paddle.into_inner();
+
+    let paddle_scale_x = PADDLE_BASE_SCALE_X
+        * (1.0
+            + WIDER_PADDLE_SCALE_PER_STACK
+                * power_up_stacks(&active_power_ups, PowerUpKind::WiderPaddle) as f32);
+    if paddle_transform.scale.x != paddle_scale_x {
+        paddle_transform.scale.x = paddle_scale_x;
+    }
+
+    let physics_relative_speed = if power_up_stacks(&active_power_ups, PowerUpKind::SlowMotion) > 0
+    {
+        SLOW_MOTION_RELATIVE_SPEED
+    } else {
+        1.0
+    };
+    if physics_time.relative_speed() != physics_relative_speed {
+        physics_time.set_relative_speed(physics_relative_speed);
+    }
+}
+
+/*
+ * While the sticky paddle is active, balls falling onto the paddle are held until launched again
+ */
+fn handle_collision_player_ball_and_sticky_paddle(
+    // Singles
+    player_paddle: Single<(Entity, &Transform), With<PlayerPaddle>>,
+    // Globals
+    mut commands: Commands,
+    active_power_ups: Res<ActivePowerUps>,
+    cursor_world_position: Res<CursorWorldPosition>,
+    // Collisions
+    collisions: Collisions,
+    // Queries
+    mut player_balls: Query<
+        (Entity, &mut Transform, &mut LinearVelocity),
+        (
+            With<PlayerBall>,
+            Without<PlayerBallInHold>,
+            Without<PlayerPaddle>,
+        ),
+    >,
+) {
+    if power_up_stacks(&active_power_ups, PowerUpKind::StickyPaddle) == 0 {
+        return;
+    }
+
+    let (paddle_entity, paddle_transform) = player_paddle.into_inner();
+
+    for (ball_entity, mut ball_transform, mut ball_velocity) in player_balls.iter_mut() {
+        // only catch falling balls, a ball that was just launched must not stick again
+        if ball_velocity.y >= 0.0 {
+            continue;
+        }
+
+        for contact_pair in collisions.iter() {
+            if (contact_pair.collider1.eq(&ball_entity) || contact_pair.collider2.eq(&ball_entity))
+                && (contact_pair.collider1.eq(&paddle_entity)
+                    || contact_pair.collider2.eq(&paddle_entity))
+            {
+                ball_velocity.0 = Vec2::ZERO;
+                ball_transform.translation.y = paddle_transform.translation.y + BALL_HOLD_OFFSET;
+
+                commands.entity(ball_entity).insert((
+                    PlayerBallInHold {
+                        aim_anchor: cursor_world_position.position,
+                    },
+                    GravityScale(0.0),
+                ));
+
+                debug!("Ball stuck to the paddle");
+                break;
+            }
+        }
+    }
+}
+
+fn reset_power_ups(
+    // Singles
+    player_paddle: Single<&mut Transform, With<PlayerPaddle>>,
+    // Globals
+    mut active_power_ups: ResMut<ActivePowerUps>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    active_power_ups.active_power_ups.clear();
+
+    player_paddle.into_inner().scale.x = PADDLE_BASE_SCALE_X;
+    physics_time.set_relative_speed(1.0);
+
+    debug!("Power-ups reset");
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin functions
+ * ================================================================================================================
+ */
+
+fn power_up_definition(kind: PowerUpKind) -> &'static PowerUpDefinition {
+    POWER_UP_DEFINITIONS
+        .iter()
+        .find(|definition| definition.kind == kind)
+        .expect("every power-up kind has a definition")
+}
+
+/*
+ * Picks a power-up definition, weighted by drop_weight
+ */
+fn roll_power_up_definition(game_rng: &mut GameRng) -> &'static PowerUpDefinition {
+    let total_weight: u32 = POWER_UP_DEFINITIONS
+        .iter()
+        .map(|definition| definition.drop_weight)
+        .sum();
+
+    let mut roll = game_rng.next_u32() % total_weight;
+
+    for definition in POWER_UP_DEFINITIONS.iter() {
+        if roll < definition.drop_weight {
+            return definition;
+        }
+        roll -= definition.drop_weight;
+    }
+
+    &POWER_UP_DEFINITIONS[0]
+}
+
+/*
+ * Adds a collected power-up to the active ones, following its stacking rule
+ */
+fn activate_power_up(active_power_ups: &mut ActivePowerUps, definition: &PowerUpDefinition) {
+    if definition.stacking == PowerUpStacking::Instant {
+        return;
+    }
+
+    if let Some(active_power_up) = active_power_ups
+        .active_power_ups
+        .iter_mut()
+        .find(|active_power_up| active_power_up.kind == definition.kind)
+    {
+        match definition.stacking {
+            PowerUpStacking::Instant => {}
+            PowerUpStacking::Refresh => {
+                active_power_up.remaining_duration = definition.duration;
+            }
+            PowerUpStacking::Extend => {
+                active_power_up.remaining_duration += definition.duration;
+            }
+            PowerUpStacking::Stack { max_stacks } => {
+                active_power_up.stacks = (active_power_up.stacks + 1).min(max_stacks);
+                active_power_up.remaining_duration = definition.duration;
+            }
+        }
+    } else {
+        active_power_ups.active_power_ups.push(ActivePowerUp {
+            kind: definition.kind,
+            remaining_duration: definition.duration,
+            stacks: 1,
+        });
+    }
+}
+
+/*
+ * Returns how many stacks of the given power-up are active; 0 if it is not active
+ */
+fn power_up_stacks(active_power_ups: &ActivePowerUps, kind: PowerUpKind) -> u32 {
+    active_power_ups
+        .active_power_ups
+        .iter()
+        .find(|active_power_up| active_power_up.kind == kind)
+        .map_or(0, |active_power_up| active_power_up.stacks)
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin functions
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Components
+ * ================================================================================================================
+ */
+
+/*
+ * Marks a falling power-up that can be caught with the paddle
+ */
+#[derive(Component)]
+struct PowerUpPickup {
+    kind: PowerUpKind,
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Components
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Bundles
+ * ================================================================================================================
+ */
+#[derive(Bundle)]
+struct PowerUpPickupBundle {
+    marker: PowerUpPickup,
+    screen_marker: OnLevelsScreen,
+    sprite: Sprite,
+    transform: Transform,
+}
+/*
+ * ================================================================================================================
+ * END - Plugin Bundles
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Enumerations
+ * ================================================================================================================
+ */
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PowerUpKind {
+    SpeedBoost,
+    PowerLevelUp,
+    MultiBall,
+    WiderPaddle,
+    SlowMotion,
+    StickyPaddle,
+    Shield,
+}
+
+/*
+ * Defines what happens when a power-up is collected while the same kind is still active
+ */
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum PowerUpStacking {
+    // applied once on pickup, never active
+    Instant,
+    // resets the remaining duration
+    Refresh,
+    // adds the duration to the remaining duration
+    Extend,
+    // adds a stack (up to max_stacks) and resets the remaining duration
+    Stack { max_stacks: u32 },
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Enumerations
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Structs
+ * ================================================================================================================
+ */
+
+struct PowerUpDefinition {
+    kind: PowerUpKind,
+    asset_path: &'static str,
+    drop_weight: u32,
+    duration: f32, // in seconds
+    stacking: PowerUpStacking,
+}
+
+struct ActivePowerUp {
+    kind: PowerUpKind,
+    remaining_duration: f32, // in seconds
+    stacks: u32,
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Structs
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * Start - Plugin Events
+ * ================================================================================================================
+ */
+#[derive(Event)]
+pub struct PowerUpCollectedEvent {
+    pub kind: PowerUpKind,
+}
+/*
+ * ================================================================================================================
+ * END - Plugin Events
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Resources
+ * ================================================================================================================
+ */
+
+/*
+ * Power-ups with a duration that are currently in effect
+ */
+#[derive(Resource, Default)]
+pub struct ActivePowerUps {
+    active_power_ups: Vec<ActivePowerUp>,
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Resources
+ * ================================================================================================================
+ */
@@ -2,7 +2,8 @@
  * Plugin to handle level selection
  */
 use super::{
-    AddCollider, BallDestroyedEvent, ColliderType, GameState, Player, PlayerBall, despawn_screen,
+    AddCollider, BallDestroyedEvent, ColliderType, GameState, GameplaySet, Player, PlayerBall,
+    despawn_screen,
 };
 use avian2d::prelude::*;
 use bevy::prelude::*;
@@ -19,8 +20,10 @@ pub fn selection_plugin(app: &mut App) {
         .add_systems(OnEnter(GameState::Selection), selection_setup)
         // While in this state, handle level selection (by handling collisions between player ball and level selector)
         .add_systems(
-            Update,
-            handle_collision_player_ball_and_selection_block.run_if(in_state(GameState::Selection)),
+            FixedPostUpdate,
+            handle_collision_player_ball_and_selection_block
+                .in_set(GameplaySet::CollisionReaction)
+                .run_if(in_state(GameState::Selection)),
         )
         // When exiting the state, despawn everything that was spawned for this screen
         .add_systems(
@@ -67,8 +70,6 @@ fn selection_setup(
 }
 
 fn handle_collision_player_ball_and_selection_block(
-    // Singles
-    player_ball: Single<Entity, With<PlayerBall>>,
     // Globals
     mut game_state: ResMut<NextState<GameState>>,
     // Collisions
@@ -76,13 +77,45 @@ fn handle_collision_player_ball_and_selection_block(
     //Queries
     level_selector_blocks: Query<(Entity, &LevelSelectorBlock), Without<PlayerBall>>,
     level_selector_outlines: Query<&LevelSelectorOutline, Without<PlayerBall>>,
+    player_balls: Query<Entity, With<PlayerBall>>,
     // Events
     mut ball_destroyed_evw: EventWriter<BallDestroyedEvent>,
     mut level_selected_evw: EventWriter<LevelSelectedEvent>,
 ) {
-    // TODO
-    let player_ball = player_ball.into_inner();
+    for player_ball in player_balls.iter() {
+        let level_selected = handle_collisions_of_player_ball(
+            player_ball,
+            &mut game_state,
+            &collisions,
+            &level_selector_blocks,
+            &level_selector_outlines,
+            &player_balls,
+            &mut ball_destroyed_evw,
+            &mut level_selected_evw,
+        );
+
+        // several balls may hit selectors in the same frame; only the first selection counts
+        if level_selected {
+            break;
+        }
+    }
+}
 
+fn handle_collisions_of_player_ball(
+    // Parameters
+    player_ball: Entity,
+    // Globals
+    game_state: &mut ResMut<NextState<GameState>>,
+    // Collisions
+    collisions: &Collisions,
+    //Queries
+    level_selector_blocks: &Query<(Entity, &LevelSelectorBlock), Without<PlayerBall>>,
+    level_selector_outlines: &Query<&LevelSelectorOutline, Without<PlayerBall>>,
+    player_balls: &Query<Entity, With<PlayerBall>>,
+    // Events
+    ball_destroyed_evw: &mut EventWriter<BallDestroyedEvent>,
+    level_selected_evw: &mut EventWriter<LevelSelectedEvent>,
+) -> bool {
     for contact_pair in collisions.iter() {
         // if a collision between the player ball and a level selector occurred
         if (contact_pair.collider1.eq(&player_ball) || contact_pair.collider2.eq(&player_ball))
@@ -101,13 +134,19 @@ fn handle_collision_player_ball_and_selection_block(
                 .unwrap()
                 .1;
 
-            ball_destroyed_evw.write(BallDestroyedEvent);
+            // all balls leave the selection screen
+            for other_player_ball in player_balls.iter() {
+                ball_destroyed_evw.write(BallDestroyedEvent {
+                    ball: other_player_ball,
+                    ball_lost: false,
+                });
+            }
             level_selected_evw.write(LevelSelectedEvent {
                 selected_level: level_selector_block.selected_level,
             });
             game_state.set(GameState::Levels);
             debug!("Selected {:?}", level_selector_block.selected_level);
-            break;
+            return true;
         }
     }
 
@@ -117,7 +156,10 @@ fn handle_collision_player_ball_and_selection_block(
             && (level_selector_outlines.contains(contact_pair.collider1)
                 || level_selector_outlines.contains(contact_pair.collider2))
         {
-            ball_destroyed_evw.write(BallDestroyedEvent);
+            ball_destroyed_evw.write(BallDestroyedEvent {
+                ball: player_ball,
+                ball_lost: false,
+            });
             debug!("Level could not be selected; not yet unlocked");
             break;
         }
@@ -125,6 +167,8 @@ fn handle_collision_player_ball_and_selection_block(
 
     // TODO read slection data from selection block and write to... Resource? how to determine which level should now run? can I just use a second GameState?
     // TODO trigger GameState change to play
+
+    false
 }
 
 /*
@@ -0,0 +1,418 @@
+/*
+ * Plugin to handle the temporary shield below the paddle
+ * While the shield has hits left, balls bounce off it instead of falling onto the bottom sensor
+ */
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{GameState, GameplaySet, PaddleMotion, PlayerBall, Playfield};
+
+use crate::levels::{BlockDestroyedEvent, OnLevelsScreen};
+
+/*
+ * Plugin defintion
+ */
+pub fn shield_plugin(app: &mut App) {
+    app
+        // When entering the state, start without a shield and without a combo
+        .add_systems(OnEnter(GameState::Levels), reset_shield)
+        // While in this state, grant shield hits, use them up on ball contacts and count combos
+        .add_systems(
+            FixedPostUpdate,
+            (
+                (
+                    handle_collision_player_ball_and_shield,
+                    handle_collision_player_ball_and_paddle,
+                )
+                    .in_set(GameplaySet::CollisionReaction),
+                (count_combo_on_block_destroyed, handle_shield_granted_event)
+                    .chain()
+                    .in_set(GameplaySet::Events),
+            )
+                .run_if(in_state(GameState::Levels)),
+        )
+        .add_systems(
+            Update,
+            update_shield.run_if(
+                in_state(GameState::Levels)
+                    .and(resource_changed::<ShieldCharges>.or(resource_changed::<Playfield>)),
+            ),
+        )
+        // Events
+        .add_event::<ShieldGrantedEvent>()
+        // Resources
+        .init_resource::<ShieldCharges>()
+        .init_resource::<Combo>();
+}
+
+// the shield never holds more hits than this; also the number of segments in its bar
+const SHIELD_MAX_HITS: u32 = 5;
+// blocks to destroy without the ball touching the paddle to earn a shield hit
+const COMBO_BLOCKS_PER_SHIELD_HIT: u32 = 6;
+// gap between two segments of the shield bar, in pixels
+const SHIELD_SEGMENT_GAP: f32 = 6.0;
+
+/*
+ * ================================================================================================================
+ * START - Plugin Systems
+ * ================================================================================================================
+ */
+
+fn reset_shield(
+    // Globals
+    mut shield_charges: ResMut<ShieldCharges>,
+    mut combo: ResMut<Combo>,
+) {
+    shield_charges.remaining_hits = 0;
+    shield_charges.touching_balls.clear();
+    combo.destroyed_blocks = 0;
+}
+
+fn handle_shield_granted_event(
+    // Globals
+    mut shield_charges: ResMut<ShieldCharges>,
+    // Events
+    mut shield_granted_evr: EventReader<ShieldGrantedEvent>,
+) {
+    for event in shield_granted_evr.read() {
+        shield_charges.remaining_hits =
+            (shield_charges.remaining_hits + event.hits).min(SHIELD_MAX_HITS);
+        debug!(
+            "Shield granted, {} hits left",
+            shield_charges.remaining_hits
+        );
+    }
+}
+
+/*
+ * Every new ball contact uses up one hit of the shield
+ */
+fn handle_collision_player_ball_and_shield(
+    // Singles
+    bottom_shield: Option<Single<Entity, With<BottomShield>>>,
+    // Globals
+    mut shield_charges: ResMut<ShieldCharges>,
+    // Collisions
+    collisions: Collisions,
+    // Queries
+    player_balls: Query<Entity, With<PlayerBall>>,
+) {
+    let Some(bottom_shield) = bottom_shield else {
+        // the next shield starts without any contacts
+        if !shield_charges.touching_balls.is_empty() {
+            shield_charges.touching_balls.clear();
+        }
+        return;
+    };
+    let bottom_shield = bottom_shield.into_inner();
+
+    // forget balls that no longer touch the shield, so their next contact counts again
+    if !shield_charges.touching_balls.is_empty() {
+        shield_charges.touching_balls.retain(|touching_ball| {
+            collisions.iter().any(|contact_pair| {
+                (contact_pair.collider1.eq(touching_ball)
+                    && contact_pair.collider2.eq(&bottom_shield))
+                    || (contact_pair.collider2.eq(touching_ball)
+                        && contact_pair.collider1.eq(&bottom_shield))
+            })
+        });
+    }
+
+    for player_ball in player_balls.iter() {
+        for contact_pair in collisions.iter() {
+            // if one of the colliders is the player ball and one of them is the shield
+            if (contact_pair.collider1.eq(&player_ball) || contact_pair.collider2.eq(&player_ball))
+                && (contact_pair.collider1.eq(&bottom_shield)
+                    || contact_pair.collider2.eq(&bottom_shield))
+            {
+                // still the same contact as in a previous frame
+                if shield_charges.touching_balls.contains(&player_ball) {
+                    break;
+                }
+                shield_charges.touching_balls.push(player_ball);
+
+                shield_charges.remaining_hits = shield_charges.remaining_hits.saturating_sub(1);
+                debug!(
+                    "Ball bounced off the shield, {} hits left",
+                    shield_charges.remaining_hits
+                );
+                break;
+            }
+        }
+    }
+}
+
+/*
+ * A combo ends as soon as a ball touches a paddle, player two's in co-op included
+ */
+fn handle_collision_player_ball_and_paddle(
+    // Globals
+    mut combo: ResMut<Combo>,
+    // Collisions
+    collisions: Collisions,
+    // Queries
+    player_balls: Query<Entity, With<PlayerBall>>,
+    paddles: Query<Entity, With<PaddleMotion>>,
+) {
+    if combo.destroyed_blocks == 0 {
+        return;
+    }
+
+    let paddle_touched = player_balls.iter().any(|player_ball| {
+        collisions.iter().any(|contact_pair| {
+            (contact_pair.collider1.eq(&player_ball) || contact_pair.collider2.eq(&player_ball))
+                && (paddles.contains(contact_pair.collider1)
+                    || paddles.contains(contact_pair.collider2))
+        })
+    });
+
+    if paddle_touched {
+        trace!("Combo of {} blocks ended", combo.destroyed_blocks);
+        combo.destroyed_blocks = 0;
+    }
+}
+
+/*
+ * Every few blocks destroyed in a row earn a shield hit
+ */
+fn count_combo_on_block_destroyed(
+    // Globals
+    mut combo: ResMut<Combo>,
+    // Events
+    mut block_destroyed_evr: EventReader<BlockDestroyedEvent>,
+    mut shield_granted_evw: EventWriter<ShieldGrantedEvent>,
+) {
+    for _event in block_destroyed_evr.read() {
+        combo.destroyed_blocks += 1;
+
+        if combo
+            .destroyed_blocks
+            .is_multiple_of(COMBO_BLOCKS_PER_SHIELD_HIT)
+        {
+            debug!(
+                "Combo of {} blocks, shield hit earned",
+                combo.destroyed_blocks
+            );
+            shield_granted_evw.write(ShieldGrantedEvent { hits: 1 });
+        }
+    }
+}
+
+/*
+ * Spawns the shield with its first hit and despawns it once all hits are used up
+ * In between, the same entity stays in place, so its contacts are tracked across hits; only its segments are recolored
+ * The shield is a bar of border sprites right below the paddle, with one segment lit per hit left
+ */
+fn update_shield(
+    // Globals
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    shield_charges: Res<ShieldCharges>,
+    playfield: Res<Playfield>,
+    // Queries
+    mut bottom_shields: Query<(Entity, &mut Transform, &mut Collider), With<BottomShield>>,
+    mut shield_segments: Query<
+        (&ShieldSegment, &mut Sprite, &mut Transform),
+        Without<BottomShield>,
+    >,
+) {
+    if shield_charges.remaining_hits == 0 {
+        for (bottom_shield, ..) in bottom_shields.iter() {
+            commands.entity(bottom_shield).despawn();
+            trace!("Shield used up");
+        }
+        return;
+    }
+
+    let shield_size = playfield.shield_size();
+
+    if bottom_shields.is_empty() {
+        commands
+            .spawn(BottomShieldBundle {
+                marker: BottomShield,
+                screen_marker: OnLevelsScreen,
+                collider: Collider::rectangle(shield_size.x, shield_size.y),
+                transform: Transform::from_xyz(0.0, playfield.shield_y(), 0.0),
+                rigid_body: RigidBody::Static,
+            })
+            .with_children(|parent| {
+                for segment_index in 0..SHIELD_MAX_HITS {
+                    let mut segment_sprite =
+                        Sprite::from_image(asset_server.load("buttonSelected.png"));
+                    segment_sprite.custom_size = Some(shield_segment_size(shield_size));
+                    segment_sprite.color =
+                        shield_segment_color(segment_index, shield_charges.remaining_hits);
+
+                    parent.spawn((
+                        ShieldSegment {
+                            index: segment_index,
+                        },
+                        segment_sprite,
+                        Transform::from_translation(shield_segment_translation(
+                            segment_index,
+                            shield_size,
+                        )),
+                    ));
+                }
+            });
+
+        trace!("Shield spawned with {} hits", shield_charges.remaining_hits);
+        return;
+    }
+
+    if playfield.is_changed() {
+        for (_bottom_shield, mut shield_transform, mut shield_collider) in bottom_shields.iter_mut()
+        {
+            shield_transform.translation.y = playfield.shield_y();
+            *shield_collider = Collider::rectangle(shield_size.x, shield_size.y);
+        }
+    }
+
+    for (shield_segment, mut segment_sprite, mut segment_transform) in shield_segments.iter_mut() {
+        segment_sprite.color =
+            shield_segment_color(shield_segment.index, shield_charges.remaining_hits);
+
+        if playfield.is_changed() {
+            segment_sprite.custom_size = Some(shield_segment_size(shield_size));
+            segment_transform.translation =
+                shield_segment_translation(shield_segment.index, shield_size);
+        }
+    }
+
+    trace!("Shield updated to {} hits", shield_charges.remaining_hits);
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Systems
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin functions
+ * ================================================================================================================
+ */
+
+fn shield_segment_size(shield_size: Vec2) -> Vec2 {
+    Vec2::new(
+        shield_size.x / SHIELD_MAX_HITS as f32 - SHIELD_SEGMENT_GAP,
+        shield_size.y,
+    )
+}
+
+fn shield_segment_translation(segment_index: u32, shield_size: Vec2) -> Vec3 {
+    let segment_length = shield_size.x / SHIELD_MAX_HITS as f32;
+
+    Vec3::new(
+        -0.5 * shield_size.x + (segment_index as f32 + 0.5) * segment_length,
+        0.0,
+        0.0,
+    )
+}
+
+/*
+ * Used up segments stay visible, but dimmed
+ */
+fn shield_segment_color(segment_index: u32, remaining_hits: u32) -> Color {
+    if segment_index >= remaining_hits {
+        Color::srgba(1.0, 1.0, 1.0, 0.25)
+    } else {
+        Color::WHITE
+    }
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin functions
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Components
+ * ================================================================================================================
+ */
+
+#[derive(Component)]
+struct BottomShield;
+
+/*
+ * One segment of the shield bar, lit while the shield has more hits left than its index
+ */
+#[derive(Component)]
+struct ShieldSegment {
+    index: u32,
+}
+
+/*
+ * ================================================================================================================
+ * END - Plugin Components
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Plugin Bundles
+ * ================================================================================================================
+ */
+#[derive(Bundle)]
+struct BottomShieldBundle {
+    marker: BottomShield,
+    screen_marker: OnLevelsScreen,
+    collider: Collider,
+    transform: Transform,
+    rigid_body: RigidBody,
+}
+/*
+ * ================================================================================================================
+ * END - Plugin Bundles
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Events
+ * ================================================================================================================
+ */
+
+/*
+ * Adds hits to the shield, e.g. from a pickup
+ */
+#[derive(Event)]
+pub struct ShieldGrantedEvent {
+    pub hits: u32,
+}
+
+/*
+ * ================================================================================================================
+ * END - Events
+ * ================================================================================================================
+ */
+
+/*
+ * ================================================================================================================
+ * START - Resources
+ * ================================================================================================================
+ */
+
+#[derive(Resource, Default)]
+struct ShieldCharges {
+    remaining_hits: u32,
+    // balls currently in contact; a ball only uses up one hit per contact
+    touching_balls: Vec<Entity>,
+}
+
+/*
+ * Blocks destroyed since a ball last touched the paddle
+ */
+#[derive(Resource, Default)]
+struct Combo {
+    destroyed_blocks: u32,
+}
+
+/*
+ * ================================================================================================================
+ * END - Resources
+ * ================================================================================================================
+ */
@@ -0,0 +1,250 @@
+/*
+ * Headless regression test for tunneling
+ * Fires balls at every element shape, the paddle and the playfield borders, so fast that a ball moves further than
+ * its own diameter plus the target's size within one physics step, and fails if any ball flies straight through
+ * Speculative contacts are turned off for the test balls, so only SweptCcd keeps them from passing through
+ * Run with: cargo test
+ */
+use avian2d::prelude::*;
+use bevy::{
+    log::LogPlugin, prelude::*, render::mesh::MeshPlugin, scene::ScenePlugin,
+    time::TimeUpdateStrategy,
+};
+
+use super::{
+    ColliderType, PADDLE_BASE_SCALE_X, PLAYER_BALL_MAX_SPEED, PLAYER_BALL_SCALE, PhysicsRules,
+    Playfield, PlayfieldBorder, apply_physics_rules, create_collider, playfield_border_transform,
+};
+
+use crate::level_elements::{
+    ElementDurability, ElementShape, assemble_asset_path, get_collider_type,
+};
+
+// distance between two test cases, so they never interact
+const TEST_CASE_SPACING: f32 = 10000.0;
+// directions each element and the paddle are shot at; borders are only shot at from inside the playfield
+const FIRING_DIRECTION_COUNT: usize = 8;
+// distance covered in one physics step, relative to the ball's diameter plus the target's size;
+// above 1, a ball without continuous collision detection can step over the target
+// deliberately stricter than the game: at PLAYER_BALL_MAX_SPEED a ball moves less than its own diameter per step,
+// which would not show whether continuous collision detection works at all
+const STEP_DISTANCE_FACTOR: f32 = 1.5;
+// physics steps before the ball would reach the target; the ball is placed so that, without continuous collision
+// detection, it would sit half a step in front of the target's center after these, and half a step past it after one more
+const STEPS_BEFORE_TARGET: usize = 2;
+// long enough for the ball to reach the target and bounce back
+const SIMULATION_STEPS: usize = STEPS_BEFORE_TARGET + 6;
+// distance from its firing line, in pixels, below which a ball is considered never to have touched its target
+const FIRING_LINE_TOLERANCE: f32 = 1.0;
+
+#[test]
+fn balls_do_not_tunnel_through_targets_even_faster_than_the_max_speed() {
+    let mut app = App::new();
+
+    app.add_plugins((
+        MinimalPlugins,
+        LogPlugin::default(),
+        TransformPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+        MeshPlugin,
+        PhysicsPlugins::default().with_length_unit(10.0),
+    ))
+    .add_systems(Startup, spawn_test_cases);
+
+    // normally done by app.run(); some plugins only set up their resources here
+    app.finish();
+    app.cleanup();
+
+    // exactly one physics step per update
+    let physics_step = app.world().resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(physics_step));
+
+    for _step in 0..SIMULATION_STEPS {
+        app.update();
+    }
+
+    let mut failed_test_cases = 0;
+    let mut test_balls = app.world_mut().query::<(&TestBall, &Transform)>();
+
+    for (test_ball, ball_transform) in test_balls.iter(app.world()) {
+        // a ball that bounced (or stopped) is still on the side it was fired from, and one that glanced off
+        // left its firing line; only a ball that never touched the target is past it and still on that line
+        let offset_from_target = ball_transform.translation.truncate() - test_ball.target_position;
+        let distance_past_target = offset_from_target.dot(test_ball.firing_direction);
+        let distance_from_firing_line = offset_from_target
+            .perp_dot(test_ball.firing_direction)
+            .abs();
+
+        if distance_past_target > 0.0 && distance_from_firing_line < FIRING_LINE_TOLERANCE {
+            error!(
+                "Ball passed through {} (fired in direction {:?})",
+                test_ball.target_name, test_ball.firing_direction
+            );
+            failed_test_cases += 1;
+        }
+    }
+
+    assert_eq!(
+        failed_test_cases, 0,
+        "balls passed through their targets, see the log above"
+    );
+}
+
+/*
+ * Spawns every target with the same collider and size as in the game, and balls fired at it
+ */
+fn spawn_test_cases(
+    // Globals
+    mut commands: Commands,
+    time: Res<Time<Fixed>>,
+    mut gravity: ResMut<Gravity>,
+    mut default_restitution: ResMut<DefaultRestitution>,
+    mut default_friction: ResMut<DefaultFriction>,
+) {
+    use core::f32::consts::TAU;
+
+    // same physics setup as in the game, but without gravity, so the balls fly in straight lines
+    apply_physics_rules(
+        &PhysicsRules {
+            gravity: Vec2::ZERO,
+            ..Default::default()
+        },
+        &mut gravity,
+        &mut default_restitution,
+        &mut default_friction,
+    );
+
+    let playfield = Playfield::default();
+    let ball_size = asset_sprite_size("ballGrey.png");
+    let ball_diameter = ball_size.x * PLAYER_BALL_SCALE;
+
+    let all_directions: Vec<Vec2> = (0..FIRING_DIRECTION_COUNT)
+        .map(|direction_index| {
+            Vec2::from_angle(TAU * direction_index as f32 / FIRING_DIRECTION_COUNT as f32)
+        })
+        .collect();
+
+    // name, collider, sprite size, transform (without translation) and firing directions of each target
+    let mut targets: Vec<(String, ColliderType, Vec2, Transform, Vec<Vec2>)> = Vec::new();
+
+    for element_shape in [
+        ElementShape::Square,
+        ElementShape::Rectangle,
+        ElementShape::Diamond,
+        ElementShape::Pentagon,
+    ] {
+        let asset_path = assemble_asset_path(&ElementDurability::Lowest, &element_shape);
+        targets.push((
+            asset_path.clone(),
+            get_collider_type(&element_shape),
+            asset_sprite_size(&asset_path),
+            Transform::default(),
+            all_directions.clone(),
+        ));
+    }
+
+    targets.push((
+        String::from("paddle"),
+        ColliderType::Capsule,
+        asset_sprite_size("paddleBlu.png"),
+        Transform::default().with_scale(Vec3::new(PADDLE_BASE_SCALE_X, 1.0, 1.0)),
+        all_directions.clone(),
+    ));
+
+    for (border_name, border, outward_direction) in [
+        ("left border", PlayfieldBorder::Left, Vec2::NEG_X),
+        ("right border", PlayfieldBorder::Right, Vec2::X),
+        ("top border", PlayfieldBorder::Top, Vec2::Y),
+    ] {
+        targets.push((
+            String::from(border_name),
+            ColliderType::RoundedRectangle,
+            asset_sprite_size("buttonSelected.png"),
+            playfield_border_transform(&playfield, &border),
+            vec![outward_direction],
+        ));
+    }
+
+    for (target_index, (target_name, collider_type, sprite_size, target_transform, directions)) in
+        targets.into_iter().enumerate()
+    {
+        // the largest extent along any firing direction; a border is only shot at across its thickness
+        let target_extent = if collider_type == ColliderType::RoundedRectangle {
+            sprite_size.y * target_transform.scale.y
+        } else {
+            (sprite_size * target_transform.scale.truncate()).max_element()
+        };
+        let step_distance = STEP_DISTANCE_FACTOR * (ball_diameter + target_extent);
+        let firing_speed = step_distance / time.timestep().as_secs_f32();
+        assert!(
+            firing_speed > PLAYER_BALL_MAX_SPEED,
+            "{} is not shot at faster than the max ball speed",
+            target_name
+        );
+        let firing_distance = (STEPS_BEFORE_TARGET as f32 + 0.5) * step_distance;
+
+        for (direction_index, firing_direction) in directions.into_iter().enumerate() {
+            let target_position = Vec2::new(
+                target_index as f32 * TEST_CASE_SPACING,
+                direction_index as f32 * TEST_CASE_SPACING,
+            );
+
+            let Some(target_collider) = create_collider(&collider_type, sprite_size) else {
+                continue;
+            };
+
+            commands.spawn((
+                target_collider,
+                RigidBody::Static,
+                target_transform.with_translation(target_position.extend(0.0)),
+            ));
+
+            let ball_position = target_position - firing_direction * firing_distance;
+
+            commands.spawn((
+                TestBall {
+                    target_name: target_name.clone(),
+                    target_position,
+                    firing_direction,
+                },
+                // same physical setup as the player ball, see player_ball_bundle,
+                // but faster than a ball in the game can ever be
+                create_collider(&ColliderType::Circle, ball_size).unwrap(),
+                Transform::from_xyz(ball_position.x, ball_position.y, 0.0).with_scale(Vec3::new(
+                    PLAYER_BALL_SCALE,
+                    PLAYER_BALL_SCALE,
+                    1.0,
+                )),
+                RigidBody::Dynamic,
+                SweptCcd::default(),
+                SpeculativeMargin(0.0),
+                LinearVelocity(firing_direction * firing_speed),
+            ));
+        }
+    }
+}
+
+/*
+ * Size of a sprite in the asset folder, read from the PNG header, since no assets are loaded in this test
+ */
+fn asset_sprite_size(asset_path: &str) -> Vec2 {
+    let png_path = format!("{}/assets/{}", env!("CARGO_MANIFEST_DIR"), asset_path);
+    let png = std::fs::read(&png_path).unwrap_or_else(|error| panic!("{}: {}", png_path, error));
+
+    // the IHDR chunk follows the 8 byte signature and the chunk's length and type; it starts with width and height
+    let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+
+    Vec2::new(width as f32, height as f32)
+}
+
+/*
+ * A ball fired at a target
+ */
+#[derive(Component)]
+struct TestBall {
+    target_name: String,
+    target_position: Vec2,
+    firing_direction: Vec2,
+}